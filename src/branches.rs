@@ -0,0 +1,188 @@
+use std::error::Error;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, Value};
+
+use crate::client::{encode_path, fetch_all_pages, fetch_all_pages_with_query, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_branches(token: &str, project_name: &str, search: Option<String>) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let url = format!("https://gitlab.com/api/v4/projects/{}/repository/branches", project_id);
+    let query: Vec<(&str, &str)> = search.iter().map(|search| ("search", search.as_str())).collect();
+    let branches = fetch_all_pages_with_query(token, &url, &query)?;
+    print_branch_table(&branches);
+    Ok(())
+}
+
+pub async fn create_branch(token: &str, project_name: &str, branch: &str, reference: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/repository/branches", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "branch": branch, "ref": reference }))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Branch `{}` was created from `{}`", branch, reference);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to create branch: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_branch(token: &str, project_name: &str, branch: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete branch `{}` in project `{}`", branch, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/repository/branches/{}", project_id, encode_path(branch)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Branch `{}` deleted successfully.", branch);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete branch: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+// Delete every unprotected branch already merged into the default branch,
+// showing the list first so it can be reviewed before confirming
+pub async fn delete_merged_branches(token: &str, project_name: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let branches = fetch_all_pages(
+        token,
+        &format!("https://gitlab.com/api/v4/projects/{}/repository/branches", project_id),
+    )?;
+    let merged: Vec<Value> = branches
+        .into_iter()
+        .filter(|branch| branch["merged"].as_bool().unwrap_or(false)
+            && !branch["protected"].as_bool().unwrap_or(false)
+            && !branch["default"].as_bool().unwrap_or(false))
+        .collect();
+
+    if merged.is_empty() {
+        println!("No merged branches to delete.");
+        return Ok(());
+    }
+
+    println!("The following {} merged branch(es) will be deleted:", merged.len());
+    print_branch_table(&merged);
+
+    let action = format!("delete {} merged branch(es) in project `{}`", merged.len(), project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+
+    for branch in merged {
+        let name = branch["name"].as_str().unwrap_or("");
+        let Some(response) = API_CLIENT
+            .delete(format!("https://gitlab.com/api/v4/projects/{}/repository/branches/{}", project_id, encode_path(name)))
+            .header("Authorization", format!("Bearer {}", token))
+            .send_unless_dry_run()?
+        else {
+            continue;
+        };
+        if response.status().is_success() {
+            println!("Branch `{}` deleted successfully.", name);
+        } else {
+            eprintln!("Failed to delete branch `{}`: {}", name, response.status());
+        }
+    }
+    Ok(())
+}
+
+pub async fn protect_branch(
+    token: &str,
+    project_name: &str,
+    branch: &str,
+    push_access_level: u64,
+    merge_access_level: u64,
+    allow_force_push: bool,
+    code_owner_approval: bool,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/protected_branches", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "name": branch,
+            "push_access_level": push_access_level,
+            "merge_access_level": merge_access_level,
+            "allow_force_push": allow_force_push,
+            "code_owner_approval_required": code_owner_approval,
+        }))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Branch `{}` is now protected.", branch);
+        Ok(())
+    } else if response.status().as_u16() == 409 {
+        Err(Box::from(format!("Branch `{}` is already protected, unprotect it first to change its settings", branch)))
+    } else {
+        let error_message = format!("Failed to protect branch: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn unprotect_branch(token: &str, project_name: &str, branch: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/protected_branches/{}", project_id, encode_path(branch)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Branch `{}` is no longer protected.", branch);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to unprotect branch: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+fn print_branch_table(branches: &[Value]) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Last Commit").add_attribute(Attribute::Bold),
+            Cell::new("Author").add_attribute(Attribute::Bold),
+            Cell::new("Committed At").add_attribute(Attribute::Bold),
+            Cell::new("Merged").add_attribute(Attribute::Bold),
+            Cell::new("Protected").add_attribute(Attribute::Bold),
+        ]);
+
+    for branch in branches {
+        let mut name_cell = Cell::new(branch["name"].as_str().unwrap_or(""));
+        if branch["default"].as_bool().unwrap_or(false) {
+            name_cell = name_cell.add_attribute(Attribute::Bold);
+        }
+        let merged_cell = if branch["merged"].as_bool().unwrap_or(false) {
+            Cell::new("yes").fg(Color::Green)
+        } else {
+            Cell::new("no")
+        };
+        table.add_row(vec![
+            name_cell,
+            Cell::new(format!(
+                "{} {}",
+                branch["commit"]["short_id"].as_str().unwrap_or(""),
+                branch["commit"]["title"].as_str().unwrap_or("")
+            )),
+            Cell::new(branch["commit"]["author_name"].as_str().unwrap_or("")),
+            Cell::new(branch["commit"]["committed_date"].as_str().unwrap_or("")),
+            merged_cell,
+            Cell::new(if branch["protected"].as_bool().unwrap_or(false) { "yes" } else { "no" }),
+        ]);
+    }
+    println!("{table}");
+}
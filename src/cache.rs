@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+// Project IDs rarely change, but projects do get renamed and deleted
+const PROJECT_ID_TTL_SECS: u64 = 24 * 60 * 60;
+const PROJECT_IDS_FILE: &str = "project_ids.json";
+const RESPONSES_DIR: &str = "responses";
+
+// GET endpoints whose responses may be kept on disk, `*` matches one path segment.
+// Anything that can carry secrets, such as variables, job traces and schedules, is left out.
+const CACHEABLE_PATHS: [&str; 30] = [
+    "projects",
+    "projects/*",
+    "groups/*/projects",
+    "namespaces/*",
+    "users",
+    "projects/*/pipelines",
+    "projects/*/pipelines/*",
+    "projects/*/pipelines/*/jobs",
+    "projects/*/pipelines/*/bridges",
+    "projects/*/pipelines/*/test_report",
+    "projects/*/jobs/*",
+    "projects/*/merge_requests",
+    "projects/*/merge_requests/*",
+    "projects/*/merge_requests/*/discussions",
+    "projects/*/issues",
+    "projects/*/issues/*",
+    "projects/*/issues/*/notes",
+    "projects/*/repository/branches",
+    "projects/*/repository/tags",
+    "projects/*/releases",
+    "projects/*/releases/*",
+    "projects/*/environments",
+    "projects/*/environments/*",
+    "projects/*/deployments",
+    "projects/*/deployments/*",
+    "projects/*/members",
+    "projects/*/members/all",
+    "groups/*/members",
+    "groups/*/members/all",
+    "projects/*/protected_branches",
+];
+
+#[derive(Serialize, Deserialize)]
+struct CachedProjectId {
+    id: u64,
+    cached_at: u64,
+}
+
+// A GET response that GitLab can confirm is unchanged with a 304
+#[derive(Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// $XDG_CACHE_HOME/labtool, falling back to ~/.cache/labtool
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir).join("labtool");
+    }
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".cache").join("labtool"),
+        None => PathBuf::from(".labtool_cache"),
+    }
+}
+
+pub fn cached_project_id(name: &str) -> Option<u64> {
+    if !is_enabled() {
+        return None;
+    }
+    let entries = read_project_ids();
+    let entry = entries.get(name)?;
+    if now().saturating_sub(entry.cached_at) > PROJECT_ID_TTL_SECS {
+        return None;
+    }
+    Some(entry.id)
+}
+
+pub fn store_project_ids(ids: &[(String, u64)]) {
+    if !is_enabled() || ids.is_empty() {
+        return;
+    }
+    let mut entries = read_project_ids();
+    let cached_at = now();
+    for (name, id) in ids {
+        entries.insert(name.clone(), CachedProjectId { id: *id, cached_at });
+    }
+    write_project_ids(&entries);
+}
+
+// Drop every name that points at a project, e.g. after it was deleted
+pub fn forget_project_id(id: u64) {
+    let mut entries = read_project_ids();
+    let before = entries.len();
+    entries.retain(|_, entry| entry.id != id);
+    if entries.len() != before {
+        write_project_ids(&entries);
+    }
+}
+
+// Whether a GET to this URL path may be cached, e.g. `/api/v4/projects/42/pipelines`
+pub fn is_cacheable(path: &str) -> bool {
+    let Some(path) = path.strip_prefix("/api/v4/") else {
+        return false;
+    };
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    CACHEABLE_PATHS.iter().any(|pattern| {
+        let pattern: Vec<&str> = pattern.split('/').collect();
+        pattern.len() == segments.len()
+            && pattern.iter().zip(&segments).all(|(expected, segment)| *expected == "*" || expected == segment)
+    })
+}
+
+// Responses are keyed by URL and token so different users never share entries.
+// SHA-256 keeps keys stable across toolchains and does not reveal the token.
+pub fn response_key(url: &str, authorization: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    hasher.update([0]);
+    hasher.update(authorization.as_bytes());
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn cached_response(key: &str) -> Option<CachedResponse> {
+    read_response(&cache_dir().join(RESPONSES_DIR), key)
+}
+
+pub fn store_response(key: &str, response: &CachedResponse) {
+    // The cache is an optimisation, failing to write it is not an error
+    let _ = write_response(&cache_dir().join(RESPONSES_DIR), key, response);
+}
+
+fn read_response(dir: &Path, key: &str) -> Option<CachedResponse> {
+    let contents = fs::read_to_string(dir.join(format!("{}.json", key))).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_response(dir: &Path, key: &str, response: &CachedResponse) -> Result<(), Box<dyn Error>> {
+    create_private_dir(dir)?;
+    write_private(&dir.join(format!("{}.json", key)), &serde_json::to_string(response)?)
+}
+
+pub fn clear() -> Result<(), Box<dyn Error>> {
+    let dir = cache_dir();
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => {
+            println!("Cache at {} was cleared.", dir.display());
+            Ok(())
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            println!("Cache at {} is already empty.", dir.display());
+            Ok(())
+        }
+        Err(e) => Err(Box::from(format!("Failed to clear cache at {}: {}", dir.display(), e))),
+    }
+}
+
+fn read_project_ids() -> HashMap<String, CachedProjectId> {
+    fs::read_to_string(cache_dir().join(PROJECT_IDS_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_project_ids(entries: &HashMap<String, CachedProjectId>) {
+    let dir = cache_dir();
+    if create_private_dir(&dir).is_ok() {
+        if let Ok(contents) = serde_json::to_string_pretty(entries) {
+            let _ = write_private(&dir.join(PROJECT_IDS_FILE), &contents);
+        }
+    }
+}
+
+// The cache holds API responses, so only the current user may read it
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    // Files written by older versions may still be world readable
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    fs::File::create(path)?.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_key_is_stable_and_per_token() {
+        let key = response_key("https://gitlab.com/api/v4/projects/1", "Bearer a");
+        assert_eq!(key, response_key("https://gitlab.com/api/v4/projects/1", "Bearer a"));
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, response_key("https://gitlab.com/api/v4/projects/1", "Bearer b"));
+        assert_ne!(key, response_key("https://gitlab.com/api/v4/projects/2", "Bearer a"));
+    }
+
+    #[test]
+    fn only_allowlisted_paths_are_cacheable() {
+        assert!(is_cacheable("/api/v4/projects/42/pipelines"));
+        assert!(is_cacheable("/api/v4/projects/42/pipelines/7/jobs"));
+        assert!(is_cacheable("/api/v4/groups/9/projects"));
+        assert!(!is_cacheable("/api/v4/projects/42/variables"));
+        assert!(!is_cacheable("/api/v4/projects/42/variables/TOKEN"));
+        assert!(!is_cacheable("/api/v4/groups/9/variables"));
+        assert!(!is_cacheable("/api/v4/projects/42/jobs/7/trace"));
+        assert!(!is_cacheable("/api/v4/projects/42/pipeline_schedules"));
+        assert!(!is_cacheable("/projects/42/pipelines"));
+    }
+
+    #[test]
+    fn stored_response_round_trips() {
+        let dir = env::temp_dir().join(format!("labtool-cache-test-{}", std::process::id()));
+        let response = CachedResponse {
+            etag: "W/\"abc\"".to_string(),
+            headers: vec![("x-total".to_string(), "3".to_string())],
+            body: "[1,2,3]".to_string(),
+        };
+        let key = response_key("https://gitlab.com/api/v4/projects", "Bearer a");
+        write_response(&dir, &key, &response).unwrap();
+
+        let cached = read_response(&dir, &key).unwrap();
+        assert_eq!(cached.etag, response.etag);
+        assert_eq!(cached.headers, response.headers);
+        assert_eq!(cached.body, response.body);
+        assert!(read_response(&dir, "missing").is_none());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let file_mode = fs::metadata(dir.join(format!("{}.json", key))).unwrap().permissions().mode();
+            assert_eq!(file_mode & 0o777, 0o600);
+            assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
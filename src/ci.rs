@@ -0,0 +1,487 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use colored::Colorize;
+use serde_json::{json, Value};
+use serde_norway::{Mapping, Value as Yaml};
+
+use crate::client::API_CLIENT;
+use crate::git_remote::git;
+use crate::projects::fetch_project_id_by_name;
+use crate::variables::fetch_effective_variables;
+
+// Lines shown before and after the line an error points at
+const CONTEXT_LINES: usize = 2;
+
+// Validate a local CI file with the project's lint API, which expands includes
+// and checks job rules the way a real pipeline would
+pub async fn lint_ci_config(
+    token: &str,
+    project_name: &str,
+    file: &Path,
+    reference: Option<String>,
+    dry_run_pipeline: bool,
+    show_merged: bool,
+) -> Result<(), Box<dyn Error>> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut body = json!({
+        "content": content,
+        "dry_run": dry_run_pipeline,
+        "include_merged_yaml": show_merged,
+    });
+    if let Some(reference) = reference {
+        body["ref"] = json!(reference);
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/ci/lint", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .read_only()
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to lint CI configuration: {}", response.text()?);
+        return Err(Box::from(error_message));
+    }
+    let result: Value = response.json()?;
+
+    for error in messages(&result, "errors") {
+        println!("{} {}", "error:".red().bold(), error);
+        print_line_context(&content, error);
+    }
+    for warning in messages(&result, "warnings") {
+        println!("{} {}", "warning:".yellow().bold(), warning);
+        print_line_context(&content, warning);
+    }
+
+    if show_merged {
+        if let Some(merged) = result["merged_yaml"].as_str().filter(|merged| !merged.is_empty()) {
+            println!("\n{}", "Merged YAML".bold());
+            println!("{}", merged);
+        }
+    }
+
+    if result["valid"].as_bool().unwrap_or(false) {
+        println!("{} {} is valid", "✔".green(), file.display());
+        Ok(())
+    } else {
+        Err(Box::from(format!("{} is invalid", file.display())))
+    }
+}
+
+fn messages<'a>(result: &'a Value, key: &str) -> Vec<&'a str> {
+    result[key]
+        .as_array()
+        .map(|messages| messages.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn print_line_context(content: &str, message: &str) {
+    let Some(line) = locate_message(content, message) else {
+        return;
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (line + CONTEXT_LINES).min(lines.len());
+    for number in first..=last {
+        let text = lines[number - 1];
+        if number == line {
+            println!("  {} {:>4} | {}", ">".red().bold(), number, text);
+        } else {
+            println!("    {:>4} | {}", number, text.dimmed());
+        }
+    }
+    println!();
+}
+
+// YAML syntax errors carry a line number, config errors name the job instead,
+// e.g. "jobs:build:script config should be an array"
+fn locate_message(content: &str, message: &str) -> Option<usize> {
+    if let Some(position) = message.find("line ") {
+        let digits: String = message[position + 5..].chars().take_while(char::is_ascii_digit).collect();
+        if let Ok(line) = digits.parse::<usize>() {
+            return Some(line).filter(|line| *line >= 1 && *line <= content.lines().count());
+        }
+    }
+
+    let path = message.split_whitespace().next()?;
+    let key = path.strip_prefix("jobs:").unwrap_or(path).split(':').next()?;
+    content
+        .lines()
+        .position(|line| line.strip_prefix(key).is_some_and(|rest| rest.trim_end() == ":" || rest.starts_with(": ")))
+        .map(|index| index + 1)
+}
+
+// Top-level keys that configure the pipeline instead of defining a job
+const GLOBAL_KEYWORDS: [&str; 10] = [
+    "default",
+    "include",
+    "stages",
+    "variables",
+    "workflow",
+    "image",
+    "services",
+    "before_script",
+    "after_script",
+    "cache",
+];
+
+// GitLab stops following `extends` after this many levels
+const MAX_EXTENDS_DEPTH: usize = 11;
+
+// Resolve one job from the local CI file and run its script in a shell or a container
+#[allow(clippy::too_many_arguments)]
+pub async fn run_local_job(
+    token: &str,
+    project_name: &str,
+    file: &Path,
+    job_name: &str,
+    include_masked: bool,
+    container: Option<String>,
+    shell: &str,
+    print_only: bool,
+) -> Result<(), Box<dyn Error>> {
+    if job_name.starts_with('.') || GLOBAL_KEYWORDS.contains(&job_name) {
+        return Err(Box::from(format!("`{}` is not a job that can be run", job_name)));
+    }
+    let root = file.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let config = load_ci_config(file, root, 0)?;
+    let job = resolve_job(&config, job_name, 0)?;
+    let defaults = config.get("default").and_then(Yaml::as_mapping);
+
+    // Job keywords win over `default:`, which wins over the legacy top-level keywords
+    let inherited = |key: &str| -> Option<&Yaml> {
+        job.get(key)
+            .or_else(|| defaults.and_then(|defaults| defaults.get(key)))
+            .or_else(|| config.get(key))
+    };
+    let before_script = match inherited("before_script") {
+        Some(value) => script_lines(&config, value)?,
+        None => Vec::new(),
+    };
+    let script = match job.get("script") {
+        Some(value) => script_lines(&config, value)?,
+        None => return Err(Box::from(format!("Job `{}` has no script", job_name))),
+    };
+    let after_script = match inherited("after_script") {
+        Some(value) => script_lines(&config, value)?,
+        None => Vec::new(),
+    };
+    let image = inherited("image").and_then(|image| match image {
+        Yaml::Mapping(image) => image.get("name").and_then(Yaml::as_str).map(String::from),
+        image => image.as_str().map(String::from),
+    });
+
+    let project_dir = match &container {
+        Some(_) => String::from("/builds/project"),
+        None => std::env::current_dir()?.display().to_string(),
+    };
+    let mut variables = BTreeMap::new();
+    variables.insert(String::from("CI"), String::from("true"));
+    variables.insert(String::from("GITLAB_CI"), String::from("true"));
+    variables.insert(String::from("CI_JOB_NAME"), job_name.to_string());
+    variables.insert(String::from("CI_PROJECT_PATH"), project_name.to_string());
+    variables.insert(String::from("CI_PROJECT_DIR"), project_dir.clone());
+    if let Some(branch) = git(&["symbolic-ref", "--short", "HEAD"]) {
+        variables.insert(String::from("CI_COMMIT_REF_NAME"), branch);
+    }
+    if let Some(sha) = git(&["rev-parse", "HEAD"]) {
+        variables.insert(String::from("CI_COMMIT_SHORT_SHA"), sha.chars().take(8).collect());
+        variables.insert(String::from("CI_COMMIT_SHA"), sha);
+    }
+    // Variables from project settings override the ones defined in the YAML
+    for source in [config.get("variables"), job.get("variables")].into_iter().flatten() {
+        variables.extend(yaml_variables(source));
+    }
+
+    let environment = match job.get("environment") {
+        Some(Yaml::Mapping(environment)) => environment.get("name").and_then(Yaml::as_str).map(String::from),
+        Some(environment) => environment.as_str().map(String::from),
+        None => None,
+    };
+    let mut file_variables = Vec::new();
+    let mut seen = HashSet::new();
+    let mut skipped_masked = 0;
+    // Without access to the project the job can still run on its YAML variables
+    let definitions = fetch_effective_variables(token, project_name).await.unwrap_or_else(|e| {
+        eprintln!("Skipping CI variables from {}: {}", project_name, e);
+        Vec::new()
+    });
+    for (_, variable) in definitions {
+        let key = variable["key"].as_str().unwrap_or("").to_string();
+        let scope = variable["environment_scope"].as_str().unwrap_or("*");
+        if !scope_matches(scope, environment.as_deref()) || !seen.insert(key.clone()) {
+            continue;
+        }
+        if variable["masked"].as_bool().unwrap_or(false) && !include_masked {
+            skipped_masked += 1;
+            continue;
+        }
+        let value = variable["value"].as_str().unwrap_or("").to_string();
+        // File variables are written out only once the job really runs
+        if variable["variable_type"].as_str() == Some("file") {
+            file_variables.push((key.clone(), value));
+            variables.insert(key, String::new());
+        } else {
+            variables.insert(key, value);
+        }
+    }
+
+    println!("{} {}", "Job".bold(), job_name);
+    if let (Some(_), Some(image)) = (&container, &image) {
+        println!("Image: {}", image);
+    }
+    println!("Variables: {}", variables.keys().cloned().collect::<Vec<String>>().join(", "));
+    if skipped_masked > 0 {
+        println!("{}", format!("{} masked variable(s) omitted, pass --include-masked to use them", skipped_masked).dimmed());
+    }
+    println!("\n{}", "Script".bold());
+    for line in before_script.iter().chain(&script) {
+        println!("$ {}", line);
+    }
+    if !after_script.is_empty() {
+        println!("\n{}", "After script".bold());
+        for line in &after_script {
+            println!("$ {}", line);
+        }
+    }
+    if print_only {
+        return Ok(());
+    }
+    println!();
+
+    // File variables hold a path to a file with the value as its contents. The
+    // directory is removed when it goes out of scope, also on errors.
+    let files_dir = match file_variables.is_empty() {
+        true => None,
+        false => Some(tempfile::Builder::new().prefix("labtool-run-").tempdir()?),
+    };
+    if let Some(files_dir) = &files_dir {
+        for (key, value) in &file_variables {
+            let path = files_dir.path().join(key);
+            write_private_file(&path, value)?;
+            variables.insert(key.clone(), path.display().to_string());
+        }
+    }
+
+    let main_lines: Vec<String> = before_script.into_iter().chain(script).collect();
+    let run = |lines: &[String]| -> Result<i32, Box<dyn Error>> {
+        let mut command = match (&container, &image) {
+            (Some(runtime), Some(image)) => {
+                let mut command = Command::new(runtime);
+                command
+                    .args(["run", "--rm", "-i", "-w", &project_dir])
+                    .arg("-v")
+                    .arg(format!("{}:{}", std::env::current_dir()?.display(), project_dir));
+                if let Some(files_dir) = &files_dir {
+                    command.arg("-v").arg(format!("{}:{}:ro", files_dir.path().display(), files_dir.path().display()));
+                }
+                // Pass names only so values never show up in the process list
+                for key in variables.keys() {
+                    command.arg("-e").arg(key);
+                }
+                command.args([image.as_str(), shell, "-c"]);
+                command
+            }
+            (Some(_), None) => return Err(Box::from(format!("Job `{}` has no image to run in a container", job_name))),
+            (None, _) => {
+                let mut command = Command::new(shell);
+                command.arg("-c");
+                command
+            }
+        };
+        let status = command.arg(shell_script(lines)).envs(&variables).status()?;
+        Ok(status.code().unwrap_or(1))
+    };
+
+    let exit_code = run(&main_lines)?;
+    // Like on a runner, after_script runs even when the script failed
+    if !after_script.is_empty() {
+        let after_code = run(&after_script)?;
+        if after_code != 0 {
+            eprintln!("after_script failed with exit code {}", after_code);
+        }
+    }
+
+    if exit_code == 0 {
+        println!("{} Job `{}` succeeded", "✔".green(), job_name);
+        Ok(())
+    } else {
+        Err(Box::from(format!("Job `{}` failed with exit code {}", job_name, exit_code)))
+    }
+}
+
+// Read a CI file and merge in its local includes, which the including file overrides
+fn load_ci_config(path: &Path, root: &Path, depth: usize) -> Result<Mapping, Box<dyn Error>> {
+    if depth > MAX_EXTENDS_DEPTH {
+        return Err(Box::from(format!("Includes are nested too deeply at {}", path.display())));
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut document: Yaml = serde_norway::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    document.apply_merge()?;
+    let mut document = match document {
+        Yaml::Mapping(document) => document,
+        Yaml::Null => Mapping::new(),
+        _ => return Err(Box::from(format!("{} is not a YAML mapping", path.display()))),
+    };
+
+    let mut config = Mapping::new();
+    let includes = match document.remove("include") {
+        Some(Yaml::Sequence(includes)) => includes,
+        Some(include) => vec![include],
+        None => Vec::new(),
+    };
+    for include in includes {
+        let local = match &include {
+            Yaml::String(location) if !location.starts_with("http") => Some(location.as_str()),
+            Yaml::Mapping(include) => include.get("local").and_then(Yaml::as_str),
+            _ => None,
+        };
+        match local {
+            Some(location) if location.contains('*') => {
+                eprintln!("Skipping include `{}`, wildcard includes are not resolved locally", location);
+            }
+            Some(location) => {
+                let included = load_ci_config(&root.join(location.trim_start_matches('/')), root, depth + 1)?;
+                deep_merge(&mut config, included);
+            }
+            None => eprintln!("Skipping include {}, only local includes are resolved", serde_norway::to_string(&include)?.trim()),
+        }
+    }
+    deep_merge(&mut config, document);
+    Ok(config)
+}
+
+// Apply `extends` by merging the parents in order, then the job itself
+fn resolve_job(config: &Mapping, name: &str, depth: usize) -> Result<Mapping, Box<dyn Error>> {
+    if depth > MAX_EXTENDS_DEPTH {
+        return Err(Box::from(format!("`extends` is nested too deeply at `{}`", name)));
+    }
+    let mut job = config
+        .get(name)
+        .and_then(Yaml::as_mapping)
+        .cloned()
+        .ok_or_else(|| format!("Job `{}` not found in the CI configuration", name))?;
+
+    let parents = match job.remove("extends") {
+        Some(Yaml::String(parent)) => vec![parent],
+        Some(Yaml::Sequence(parents)) => parents.iter().filter_map(Yaml::as_str).map(String::from).collect(),
+        _ => Vec::new(),
+    };
+    let mut resolved = Mapping::new();
+    for parent in parents {
+        deep_merge(&mut resolved, resolve_job(config, &parent, depth + 1)?);
+    }
+    deep_merge(&mut resolved, job);
+    Ok(resolved)
+}
+
+// Hashes are merged key by key, any other value replaces the one it overrides
+fn deep_merge(base: &mut Mapping, other: Mapping) {
+    for (key, value) in other {
+        match (base.get_mut(&key), value) {
+            (Some(Yaml::Mapping(existing)), Yaml::Mapping(value)) => deep_merge(existing, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// Flatten a script into lines, following `!reference [job, key]` tags
+fn script_lines(config: &Mapping, value: &Yaml) -> Result<Vec<String>, Box<dyn Error>> {
+    match value {
+        Yaml::String(line) => Ok(vec![line.clone()]),
+        Yaml::Number(_) | Yaml::Bool(_) => Ok(vec![scalar_to_string(value)]),
+        Yaml::Sequence(items) => {
+            let mut lines = Vec::new();
+            for item in items {
+                lines.extend(script_lines(config, item)?);
+            }
+            Ok(lines)
+        }
+        Yaml::Tagged(tagged) if tagged.tag == "reference" => {
+            let path: Vec<&str> = tagged.value.as_sequence().into_iter().flatten().filter_map(Yaml::as_str).collect();
+            let (job, keys) = path.split_first().ok_or("Empty !reference tag")?;
+            let job = resolve_job(config, job, 0)?;
+            let mut target = job.get(keys.first().copied().unwrap_or_default());
+            for key in keys.iter().skip(1) {
+                target = target.and_then(|value| value.get(key));
+            }
+            let target = target.ok_or_else(|| format!("!reference {:?} does not exist", path))?;
+            script_lines(config, target)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn yaml_variables(variables: &Yaml) -> Vec<(String, String)> {
+    let Some(variables) = variables.as_mapping() else {
+        return Vec::new();
+    };
+    variables
+        .iter()
+        .filter_map(|(key, value)| {
+            // Variables may be written as `KEY: value` or `KEY: { value: ..., description: ... }`
+            let value = match value {
+                Yaml::Mapping(definition) => definition.get("value")?,
+                value => value,
+            };
+            Some((key.as_str()?.to_string(), scalar_to_string(value)))
+        })
+        .collect()
+}
+
+fn scalar_to_string(value: &Yaml) -> String {
+    match value {
+        Yaml::String(text) => text.clone(),
+        Yaml::Number(number) => number.to_string(),
+        Yaml::Bool(flag) => flag.to_string(),
+        _ => String::new(),
+    }
+}
+
+// `*` matches everything, a trailing `*` matches a prefix such as `review/*`
+fn scope_matches(scope: &str, environment: Option<&str>) -> bool {
+    if scope == "*" {
+        return true;
+    }
+    match (environment, scope.strip_suffix('*')) {
+        (Some(environment), Some(prefix)) => environment.starts_with(prefix),
+        (Some(environment), None) => environment == scope,
+        (None, _) => false,
+    }
+}
+
+// Stop at the first failing line and echo each line before it runs, like a runner does
+// Only the current user may read the values of file variables
+#[cfg(unix)]
+fn write_private_file(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+fn shell_script(lines: &[String]) -> String {
+    let mut script = String::from("set -e\n");
+    for line in lines {
+        let echo = format!("$ {}", line).replace('\'', "'\\''");
+        script.push_str(&format!("printf '%s\\n' '{}'\n{}\n", echo, line));
+    }
+    script
+}
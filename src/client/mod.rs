@@ -1,6 +1,317 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use lazy_static::lazy_static;
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Request, RequestBuilder, Response};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{IntoUrl, Method, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache::{self, CachedResponse};
 
 lazy_static! {
-   pub static ref API_CLIENT: Client = Client::new();
+   pub static ref API_CLIENT: ApiClient = ApiClient { inner: Client::new() };
+}
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+// Fields whose values are never printed by --dry-run
+const REDACTED_FIELDS: [&str; 4] = ["value", "token", "password", "secret"];
+
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+// Returned instead of a response when --dry-run stops a mutating request.
+// `main` treats it as success, so `?` ends the command after the request is shown.
+#[derive(Debug)]
+pub struct DryRun;
+
+impl fmt::Display for DryRun {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "request not sent because of --dry-run")
+    }
+}
+
+impl Error for DryRun {}
+
+// Thin wrapper around the blocking client so every mutating request can be
+// intercepted in one place when --dry-run is active
+pub struct ApiClient {
+    inner: Client,
+}
+
+impl ApiClient {
+    pub fn get<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        ApiRequest { inner: self.inner.get(url), read_only: true }
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        ApiRequest { inner: self.inner.post(url), read_only: false }
+    }
+
+    pub fn put<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        ApiRequest { inner: self.inner.put(url), read_only: false }
+    }
+
+    pub fn delete<U: IntoUrl>(&self, url: U) -> ApiRequest {
+        ApiRequest { inner: self.inner.delete(url), read_only: false }
+    }
+}
+
+pub struct ApiRequest {
+    inner: RequestBuilder,
+    read_only: bool,
+}
+
+impl ApiRequest {
+    pub fn header<K, V>(self, key: K, value: V) -> ApiRequest
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        ApiRequest { inner: self.inner.header(key, value), ..self }
+    }
+
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> ApiRequest {
+        ApiRequest { inner: self.inner.query(query), ..self }
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> ApiRequest {
+        ApiRequest { inner: self.inner.json(json), ..self }
+    }
+
+    // POSTs that only read, such as GraphQL queries and CI lint, still go out under --dry-run
+    pub fn read_only(self) -> ApiRequest {
+        ApiRequest { read_only: true, ..self }
+    }
+
+    // Send the request, or stop with `DryRun` when --dry-run holds back a mutating one
+    pub fn send(self) -> Result<Response, Box<dyn Error>> {
+        match self.send_unless_dry_run()? {
+            Some(response) => Ok(response),
+            None => Err(Box::new(DryRun)),
+        }
+    }
+
+    // Like `send`, but returns None for a held back request so loops can
+    // show every request they would make
+    pub fn send_unless_dry_run(self) -> Result<Option<Response>, Box<dyn Error>> {
+        let (client, request) = self.inner.build_split();
+        let request = request?;
+
+        if request.method() == Method::GET {
+            return execute_cached(&client, request).map(Some);
+        }
+        if self.read_only || !is_dry_run() {
+            return Ok(Some(client.execute(request)?));
+        }
+
+        println!("[dry-run] Would send {} {}", request.method(), redact_url(request.url()));
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(mut json) => {
+                    redact_json(&mut json);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                Err(_) => println!("<{} byte body>", body.len()),
+            }
+        }
+        Ok(None)
+    }
+}
+
+// Revalidate GET requests with If-None-Match and answer a 304 from the cache
+fn execute_cached(client: &Client, mut request: Request) -> Result<Response, Box<dyn Error>> {
+    if !cache::is_enabled() || !cache::is_cacheable(request.url().path()) {
+        return Ok(client.execute(request)?);
+    }
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let key = cache::response_key(request.url().as_str(), authorization);
+    let cached = cache::cached_response(&key);
+    if let Some(cached) = &cached {
+        request.headers_mut().insert(IF_NONE_MATCH, HeaderValue::from_str(&cached.etag)?);
+    }
+
+    let response = client.execute(request)?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            return cached_to_response(cached.headers, cached.body);
+        }
+    }
+    let etag = match response.headers().get(ETAG).and_then(|value| value.to_str().ok()) {
+        Some(etag) if response.status() == StatusCode::OK => etag.to_string(),
+        _ => return Ok(response),
+    };
+
+    // The body can only be read once, so rebuild the response after storing it
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let body = response.text()?;
+    let cached = CachedResponse { etag, headers, body };
+    cache::store_response(&key, &cached);
+    cached_to_response(cached.headers, cached.body)
+}
+
+fn cached_to_response(headers: Vec<(String, String)>, body: String) -> Result<Response, Box<dyn Error>> {
+    let mut builder = http::Response::builder().status(200);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    Ok(Response::from(builder.body(body)?))
+}
+
+fn is_redacted(field: &str) -> bool {
+    REDACTED_FIELDS.iter().any(|redacted| field.to_lowercase().contains(redacted))
+}
+
+fn redact_url(url: &reqwest::Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = if is_redacted(&key) { String::from("[REDACTED]") } else { value.into_owned() };
+            (key.into_owned(), value)
+        })
+        .collect();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+fn redact_json(json: &mut Value) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_redacted(key) && !value.is_null() {
+                    *value = Value::String(String::from("[REDACTED]"));
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+// Fetch every page of a list endpoint by following GitLab's `x-next-page` header
+pub fn fetch_all_pages(token: &str, url: &str) -> Result<Vec<Value>, Box<dyn Error>> {
+    fetch_all_pages_with_query(token, url, &[])
+}
+
+// Same as `fetch_all_pages`, with query parameters that are encoded for the caller
+pub fn fetch_all_pages_with_query(token: &str, url: &str, query: &[(&str, &str)]) -> Result<Vec<Value>, Box<dyn Error>> {
+    let (items, _) = fetch_limited_pages(token, url, query, usize::MAX)?;
+    Ok(items)
+}
+
+// Stop paging once `limit` items are fetched, also returning whether there were more
+pub fn fetch_limited_pages(
+    token: &str,
+    url: &str,
+    query: &[(&str, &str)],
+    limit: usize,
+) -> Result<(Vec<Value>, bool), Box<dyn Error>> {
+    let mut items = Vec::new();
+    let mut page = String::from("1");
+
+    loop {
+        let response = API_CLIENT
+            .get(url)
+            .query(query)
+            .query(&[("per_page", "100"), ("page", page.as_str())])
+            .header("Authorization", format!("Bearer {}", token))
+            .send()?;
+
+        if !response.status().is_success() {
+            return Err(Box::from(format!("Request to {} failed: {}", url, response.status())));
+        }
+
+        let next_page = response
+            .headers()
+            .get("x-next-page")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+
+        let body: Value = response.json()?;
+        if let Some(array) = body.as_array() {
+            items.extend(array.iter().cloned());
+        }
+
+        if items.len() >= limit {
+            let more = items.len() > limit || !next_page.is_empty();
+            items.truncate(limit);
+            return Ok((items, more));
+        }
+        if next_page.is_empty() {
+            break;
+        }
+        page = next_page;
+    }
+
+    Ok((items, false))
+}
+
+// Encode a full path such as `my-group/sub-group` for use as an `:id` segment
+pub fn encode_path(path: &str) -> String {
+    path.replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redact_json_hides_secret_fields_at_any_depth() {
+        let mut body = json!({
+            "key": "DEPLOY_KEY",
+            "value": "hunter2",
+            "masked": true,
+            "variables": [{ "key": "A", "secret_value": "s3cr3t" }],
+            "runner": { "token": "glrt-abc", "description": "shared" },
+            "password": null,
+        });
+        redact_json(&mut body);
+        assert_eq!(body["key"], "DEPLOY_KEY");
+        assert_eq!(body["value"], "[REDACTED]");
+        assert_eq!(body["masked"], true);
+        assert_eq!(body["variables"][0]["secret_value"], "[REDACTED]");
+        assert_eq!(body["runner"]["token"], "[REDACTED]");
+        assert_eq!(body["runner"]["description"], "shared");
+        // Null fields carry nothing to hide
+        assert!(body["password"].is_null());
+    }
+
+    #[test]
+    fn redact_url_hides_secret_query_parameters() {
+        let url = reqwest::Url::parse("https://gitlab.com/api/v4/projects/1/variables?key=A&value=hunter2").unwrap();
+        let redacted = redact_url(&url);
+        assert!(redacted.contains("key=A"));
+        assert!(!redacted.contains("hunter2"));
+    }
+
+    #[test]
+    fn redact_url_leaves_urls_without_query_alone() {
+        let url = reqwest::Url::parse("https://gitlab.com/api/v4/projects/1").unwrap();
+        assert_eq!(redact_url(&url), "https://gitlab.com/api/v4/projects/1");
+    }
 }
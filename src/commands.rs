@@ -1,10 +1,22 @@
-use clap::{arg, Parser, Subcommand};
-use std::{fs, io::Error as IoError};
+use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(name = "labtool")]
 #[command(about = "CLI for managing GitLab via their API", long_about = None)]
 pub struct Cli {
+    /// Skip confirmation prompts for destructive actions
+    #[arg(short = 'y', long, global = true, default_value_t = false)]
+    pub yes: bool,
+    /// Print mutating POST, PUT and DELETE requests instead of sending them
+    #[arg(long, global = true, default_value_t = false)]
+    pub dry_run: bool,
+    /// Bypass the local cache of project IDs and API responses
+    #[arg(long, global = true, default_value_t = false)]
+    pub no_cache: bool,
+    /// Show the project and branch inferred from the current git repository
+    #[arg(long, global = true, default_value_t = false)]
+    pub verbose: bool,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -26,52 +38,249 @@ pub enum Commands {
         #[command(subcommand)]
         action: PipelineActions,
     },
+    /// Validate and debug GitLab CI configuration
+    Ci {
+        #[command(subcommand)]
+        action: CiActions,
+    },
     Variables {
         #[command(subcommand)]
         action: VariablesActions
     },
+    /// View and manage merge requests
+    #[command(name = "mr")]
+    MergeRequests {
+        #[command(subcommand)]
+        action: MergeRequestActions,
+    },
+    /// View and manage issues
+    Issues {
+        #[command(subcommand)]
+        action: IssueActions,
+    },
+    /// View, create, delete and protect branches
+    Branches {
+        #[command(subcommand)]
+        action: BranchActions,
+    },
+    /// View, create and delete tags
+    Tags {
+        #[command(subcommand)]
+        action: TagActions,
+    },
+    /// View and manage releases
+    Releases {
+        #[command(subcommand)]
+        action: ReleaseActions,
+    },
+    /// View, create and run pipeline schedules
+    Schedules {
+        #[command(subcommand)]
+        action: ScheduleActions,
+    },
+    /// View, stop and delete environments
+    Environments {
+        #[command(subcommand)]
+        action: EnvironmentActions,
+    },
+    /// View deployments and roll back environments
+    Deployments {
+        #[command(subcommand)]
+        action: DeploymentActions,
+    },
+    /// Inspect and manage CI runners
+    Runners {
+        #[command(subcommand)]
+        action: RunnerActions,
+    },
+    /// View and manage project and group members
+    Members {
+        #[command(subcommand)]
+        action: MemberActions,
+    },
+    /// Manage the local cache of project IDs and API responses
+    Cache {
+        #[command(subcommand)]
+        action: CacheActions,
+    },
 }
 
 
 #[derive(Subcommand)]
 pub enum ProjectActions {
-    List {},
+    // labtool projects list --group my-group --include-subgroups --search api --sort name
+    /// list projects, the default group unless another scope is given
+    List {
+        #[arg(short, long)]
+        search: Option<String>,
+        /// projects you own, across all groups
+        #[arg(long, default_value_t = false)]
+        owned: bool,
+        /// projects you are a member of, across all groups
+        #[arg(long, default_value_t = false)]
+        membership: bool,
+        #[arg(long, default_value_t = false)]
+        starred: bool,
+        /// only archived projects
+        #[arg(long, default_value_t = false)]
+        archived: bool,
+        #[arg(long, value_parser = ["private", "internal", "public"])]
+        visibility: Option<String>,
+        #[arg(long)]
+        topic: Option<String>,
+        #[arg(long)]
+        language: Option<String>,
+        #[arg(long, value_enum, default_value_t = ProjectSort::LastActivity)]
+        sort: ProjectSort,
+        #[arg(short, long)]
+        group: Option<String>,
+        #[arg(long, default_value_t = false, requires = "group")]
+        include_subgroups: bool,
+    },
     View {
         #[arg(short, long)]
-        name: String
+        name: String,
+        /// print the full project document with `json`
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
+    // labtool projects create --name new-service --namespace my-group --visibility private --template express
+    /// create a new project, optionally from a template
+    Create {
+        #[arg(short, long)]
+        name: String,
+        #[arg(long)]
+        path: Option<String>,
+        #[arg(long)]
+        namespace: Option<String>,
+        #[arg(long, value_parser = ["private", "internal", "public"])]
+        visibility: Option<String>,
+        #[arg(long)]
+        default_branch: Option<String>,
+        #[arg(short, long)]
+        description: Option<String>,
+        /// name of a built-in project template
+        #[arg(long, conflicts_with = "template_project_id")]
+        template: Option<String>,
+        /// ID of a custom template project
+        #[arg(long)]
+        template_project_id: Option<u64>,
+    },
+    // labtool projects fork --name testproject --namespace my-user
+    Fork {
+        #[arg(short, long)]
+        name: String,
+        #[arg(long)]
+        namespace: Option<String>,
+        #[arg(long)]
+        fork_name: Option<String>,
+    },
+    /// make a project read-only
+    Archive {
+        #[arg(short, long)]
+        name: String,
+    },
+    Unarchive {
+        #[arg(short, long)]
+        name: String,
+    },
+    Delete {
+        #[arg(short, long)]
+        name: String,
+    },
+    // labtool projects settings set --name testproject merge_method=ff ci_config_path=ci/main.yml
+    /// view and change common project settings
+    Settings {
+        #[command(subcommand)]
+        action: ProjectSettingsAction,
     },
-
 }
+#[derive(Subcommand)]
+pub enum ProjectSettingsAction {
+    Get {
+        #[arg(short, long)]
+        name: String,
+    },
+    Set {
+        #[arg(short, long)]
+        name: String,
+        /// settings as key=value
+        #[arg(required = true)]
+        settings: Vec<String>,
+    },
+}
+// Which project, group or instance a variables command operates on,
+// defaulting to the project of the current git repository
+#[derive(Args)]
+#[group(multiple = false)]
+pub struct VariableTarget {
+    #[arg(short, long)]
+    pub project_name: Option<String>,
+    #[arg(short, long)]
+    pub group: Option<String>,
+    /// Instance-level variables, requires an administrator token
+    #[arg(long, default_value_t = false)]
+    pub instance: bool,
+}
+
 #[derive(Subcommand)]
 pub enum VariablesActions {
-    //labtoool projects variables list --project-name
+    //labtoool variables list --project-name | --group | --instance
     List {
-        #[arg(short, long)]
-        project_name: String
+        #[command(flatten)]
+        target: VariableTarget,
     },
-    //labtoool projects variables update --project-name --key --value
+    //labtoool variables update --project-name --key --value
     Update {
-        #[arg(short, long)]
-        project_name: String,
+        #[command(flatten)]
+        target: VariableTarget,
         #[arg(short, long)]
         key: String,
         #[arg(short, long)]
         value: String,
     },
     Set {
-        #[arg(short, long)]
-        project_name: String,
+        #[command(flatten)]
+        target: VariableTarget,
         #[arg(short, long)]
         key: String,
         #[arg(short, long)]
         value: String,
     },
     Delete {
-        #[arg(short, long)]
-        project_name: String,
+        #[command(flatten)]
+        target: VariableTarget,
         #[arg(short, long)]
         key: String,
     },
+    //labtool variables effective --project-name my-service
+    /// Show project variables together with inherited group and instance variables
+    Effective {
+        #[arg(short, long)]
+        project_name: Option<String>,
+    },
+    //labtool variables copy --from template-project --to new-service --keys K1,K2 --overwrite
+    /// Copy variables between projects, groups and the instance, keeping every attribute
+    Copy {
+        #[arg(long, required_unless_present_any = ["from_group", "instance"], conflicts_with_all = ["from_group", "instance"])]
+        from: Option<String>,
+        #[arg(long, conflicts_with = "instance")]
+        from_group: Option<String>,
+        /// copy from the instance variables, requires an administrator token
+        #[arg(long, default_value_t = false)]
+        instance: bool,
+        #[arg(long, required_unless_present_any = ["to_group", "to_instance"], conflicts_with_all = ["to_group", "to_instance"])]
+        to: Option<String>,
+        #[arg(long, conflicts_with = "to_instance")]
+        to_group: Option<String>,
+        /// copy to the instance variables, requires an administrator token
+        #[arg(long, default_value_t = false)]
+        to_instance: bool,
+        #[arg(long, value_delimiter = ',')]
+        keys: Option<Vec<String>>,
+        #[arg(long, default_value_t = false)]
+        overwrite: bool,
+    },
 }
 #[derive(Subcommand, Clone)]
 pub enum PipelineActions {
@@ -79,22 +288,102 @@ pub enum PipelineActions {
     /// list pipeliens for specific project
     List {
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
         #[arg(short, long)]
         last: Option<String>,
         #[arg(short, long)]
         branch: Option<String>,
         #[arg(short, long)]
         status: Option<String>,
-        #[arg(short, long, default_value_t = false)]
+        #[arg(long, default_value_t = false)]
         show_jobs: bool,
+        /// how many levels of downstream pipelines to show with --show-jobs
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+        /// add a coverage column, which takes one more request per pipeline
+        #[arg(long, default_value_t = false, conflicts_with = "show_jobs")]
+        coverage: bool,
     },
     // labtool pipelines trigger --project testproject --branch main
     Trigger {
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+    // labtool pipelines delete --project testproject --pipeline 1538279316
+    /// delete a pipeline and its jobs
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(long = "pipeline", alias = "pipeline-id")]
+        pipeline_id: i64,
+    },
+    // labtool pipelines graph --project testproject --pipeline 1538279316 --format mermaid
+    /// show the stages and job dependencies of a pipeline
+    Graph {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(long = "pipeline")]
+        pipeline_id: i64,
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::Ascii)]
+        format: GraphFormat,
+    },
+    // labtool pipelines why --project testproject --pipeline 1538279316 | --latest --branch main
+    /// explain why a pipeline failed with excerpts from the failed jobs' logs
+    Why {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(long = "pipeline", conflicts_with = "latest", required_unless_present = "latest")]
+        pipeline_id: Option<i64>,
+        /// use the latest pipeline on --branch, or on the current branch
+        #[arg(long, default_value_t = false)]
+        latest: bool,
         #[arg(short, long)]
-        branch: String,
+        branch: Option<String>,
+        /// number of log lines to show for each failed job
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+    },
+    // labtool pipelines stats --project testproject --last 30d --branch main --output json
+    /// report success rate, durations, queue times and flaky jobs
+    Stats {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long, default_value = "30d")]
+        last: String,
+        #[arg(short, long)]
+        branch: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
+    // labtool pipelines tests --project testproject --pipeline 1538279316 --compare 1538270000
+    /// show the test report of a pipeline
+    Tests {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(long = "pipeline")]
+        pipeline_id: i64,
+        /// only show suites with failures and the failed tests
+        #[arg(long, default_value_t = false)]
+        failed_only: bool,
+        /// show tests that started failing or were fixed since this pipeline
+        #[arg(long, value_name = "PIPELINE_ID", conflicts_with = "failed_only")]
+        compare: Option<i64>,
+    },
+    // labtool pipelines coverage --project testproject --last 30d --branch main --threshold 0.5
+    /// show the coverage trend of a branch and fail when the latest pipeline dropped
+    Coverage {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long, default_value = "30d")]
+        last: String,
+        /// defaults to the checked out branch of the current repository
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// largest allowed drop in percentage points
+        #[arg(short, long, default_value_t = 1.0)]
+        threshold: f64,
     },
     // labtool pipelines jobs logs --project testproject --job-id 2924792047
     ///view job logs
@@ -107,8 +396,758 @@ pub enum PipelineActions {
 pub enum JobAction {
     Logs {
         #[arg(short, long)]
-        project: String,
+        project: Option<String>,
+        #[arg(short, long)]
+        job_id: i64,
+    },
+    // labtool pipelines jobs retry --project testproject --job-id 2924792047
+    /// retry a finished job
+    Retry {
+        #[arg(short, long)]
+        project: Option<String>,
         #[arg(short, long)]
         job_id: i64,
     },
+    // labtool pipelines jobs play --project testproject --job-id 2924792047
+    /// start a manual job
+    Play {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        job_id: i64,
+    },
+    // labtool pipelines jobs erase --project testproject --job-id 2924792047
+    /// erase a job's log and artifacts
+    Erase {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        job_id: i64,
+    },
+}
+#[derive(Subcommand)]
+pub enum MergeRequestActions {
+    // labtool mr list --project testproject --state opened --author jdoe --labels bug,backend
+    /// list merge requests, open ones by default
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        /// opened, closed, merged, locked or all
+        #[arg(short, long)]
+        state: Option<String>,
+        #[arg(short, long)]
+        author: Option<String>,
+        #[arg(short, long)]
+        reviewer: Option<String>,
+        #[arg(short, long, value_delimiter = ',')]
+        labels: Option<Vec<String>>,
+        #[arg(short, long)]
+        target_branch: Option<String>,
+    },
+    // labtool mr view --project testproject --iid 42
+    /// show a merge request with its pipeline, approvals and discussions
+    View {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    // labtool mr create --project testproject --source-branch feature --target-branch main --title "Add feature" --editor
+    /// open a new merge request
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        source_branch: String,
+        /// defaults to the project's default branch
+        #[arg(long)]
+        target_branch: Option<String>,
+        #[arg(short, long)]
+        title: String,
+        #[arg(short, long, conflicts_with_all = ["description_file", "editor"])]
+        description: Option<String>,
+        #[arg(long, conflicts_with = "editor")]
+        description_file: Option<PathBuf>,
+        /// write the description in $EDITOR
+        #[arg(short, long, default_value_t = false)]
+        editor: bool,
+        #[arg(short, long, value_delimiter = ',')]
+        assignees: Vec<String>,
+    },
+    // labtool mr approve --project testproject --iid 42
+    /// approve a merge request
+    Approve {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    // labtool mr merge --project testproject --iid 42 --when-pipeline-succeeds --squash
+    /// merge now, or once the pipeline succeeds
+    Merge {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(long, default_value_t = false)]
+        when_pipeline_succeeds: bool,
+        #[arg(long, default_value_t = false)]
+        squash: bool,
+        #[arg(long, requires = "squash")]
+        squash_message: Option<String>,
+        #[arg(long, default_value_t = false)]
+        remove_source_branch: bool,
+    },
+    /// close a merge request without merging it
+    Close {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    /// reopen a closed merge request
+    Reopen {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    // labtool mr comments --project testproject --iid 42 --unresolved
+    /// show threaded discussions with their resolved state and diff positions
+    Comments {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(short, long, default_value_t = false)]
+        unresolved: bool,
+    },
+    // labtool mr comment --project testproject --iid 42 --body "Looks good" [--discussion abc123]
+    /// add a note, or reply to a thread with --discussion
+    Comment {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(long)]
+        discussion: Option<String>,
+        #[arg(short, long, conflicts_with_all = ["body_file", "editor"])]
+        body: Option<String>,
+        #[arg(long, conflicts_with = "editor")]
+        body_file: Option<PathBuf>,
+        /// write the comment in $EDITOR
+        #[arg(short, long, default_value_t = false)]
+        editor: bool,
+    },
+    // labtool mr resolve --project testproject --iid 42 --discussion abc123
+    /// resolve a thread, or reopen it with --unresolve
+    Resolve {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(long)]
+        discussion: String,
+        #[arg(long, default_value_t = false)]
+        unresolve: bool,
+    },
+    // labtool mr diff --project testproject --iid 42
+    /// print a coloured unified diff of the merge request changes
+    Diff {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+}
+#[derive(Subcommand)]
+pub enum IssueActions {
+    // labtool issues list --project testproject --labels bug --milestone "v1.2" --search crash
+    /// list issues, open ones by default
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long, value_delimiter = ',')]
+        labels: Option<Vec<String>>,
+        #[arg(short, long)]
+        milestone: Option<String>,
+        #[arg(short, long)]
+        assignee: Option<String>,
+        /// opened, closed or all
+        #[arg(short, long)]
+        state: Option<String>,
+        #[arg(long)]
+        search: Option<String>,
+    },
+    // labtool issues view --project testproject --iid 7
+    /// show an issue with its comments
+    View {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    // labtool issues create --project testproject --title "Crash on start" --editor --labels bug
+    /// open a new issue
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        title: String,
+        #[arg(short, long, conflicts_with_all = ["description_file", "editor"])]
+        description: Option<String>,
+        #[arg(long, conflicts_with = "editor")]
+        description_file: Option<PathBuf>,
+        /// write the description in $EDITOR
+        #[arg(short, long, default_value_t = false)]
+        editor: bool,
+        #[arg(short, long, value_delimiter = ',')]
+        labels: Option<Vec<String>>,
+        #[arg(short, long)]
+        assignee: Option<String>,
+        #[arg(short, long)]
+        milestone: Option<String>,
+    },
+    /// close an issue
+    Close {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    /// reopen a closed issue
+    Reopen {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+    },
+    // labtool issues comment --project testproject --iid 7 --body "Fixed in !42"
+    /// add a comment to an issue
+    Comment {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(short, long, conflicts_with_all = ["body_file", "editor"])]
+        body: Option<String>,
+        #[arg(long, conflicts_with = "editor")]
+        body_file: Option<PathBuf>,
+        /// write the comment in $EDITOR
+        #[arg(short, long, default_value_t = false)]
+        editor: bool,
+    },
+    // labtool issues label add --project testproject --iid 7 --labels bug,critical
+    /// add or remove issue labels
+    Label {
+        #[command(subcommand)]
+        action: IssueLabelAction,
+    },
+}
+#[derive(Subcommand)]
+pub enum IssueLabelAction {
+    Add {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        labels: Vec<String>,
+    },
+    Remove {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        iid: u64,
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        labels: Vec<String>,
+    },
+}
+#[derive(Subcommand)]
+pub enum BranchActions {
+    // labtool branches list --project testproject --search feature
+    /// list branches with their last commit and merged status
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        search: Option<String>,
+    },
+    // labtool branches create --project testproject --name feature --ref main
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+        #[arg(short, long = "ref")]
+        reference: String,
+    },
+    // labtool branches delete --project testproject --name feature | --merged
+    /// delete a branch, or every branch merged into the default branch
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long, required_unless_present = "merged", conflicts_with = "merged")]
+        name: Option<String>,
+        #[arg(short, long, default_value_t = false)]
+        merged: bool,
+    },
+    // labtool branches protect --project testproject --name main --push maintainer --merge developer
+    Protect {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+        #[arg(long, value_enum, default_value_t = ProtectedAccessLevel::Maintainer)]
+        push: ProtectedAccessLevel,
+        #[arg(long, value_enum, default_value_t = ProtectedAccessLevel::Maintainer)]
+        merge: ProtectedAccessLevel,
+        #[arg(long, default_value_t = false)]
+        allow_force_push: bool,
+        /// require approval from code owners for changes to owned files
+        #[arg(long, default_value_t = false)]
+        code_owner_approval: bool,
+    },
+    Unprotect {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+    },
+}
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ProtectedAccessLevel {
+    NoOne,
+    Developer,
+    Maintainer,
+    Admin,
+}
+impl ProtectedAccessLevel {
+    pub fn level(self) -> u64 {
+        match self {
+            ProtectedAccessLevel::NoOne => 0,
+            ProtectedAccessLevel::Developer => 30,
+            ProtectedAccessLevel::Maintainer => 40,
+            ProtectedAccessLevel::Admin => 60,
+        }
+    }
+}
+#[derive(Subcommand)]
+pub enum TagActions {
+    // labtool tags list --project testproject --search v1
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        search: Option<String>,
+    },
+    // labtool tags create --project testproject --name v1.2.0 --ref main --message "Release 1.2.0"
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+        #[arg(short, long = "ref")]
+        reference: String,
+        /// create an annotated tag with this message
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+    },
+}
+#[derive(Subcommand)]
+pub enum ReleaseActions {
+    // labtool releases list --project testproject
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+    // labtool releases view --project testproject --tag v1.2.0
+    View {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        tag: String,
+    },
+    // labtool releases create --project testproject --tag v1.2.0 --changelog CHANGELOG.md --from-pipeline 1538279316
+    /// create a release, creating the tag from --ref when it does not exist yet
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        tag: String,
+        #[arg(short, long)]
+        name: Option<String>,
+        #[arg(short, long = "ref")]
+        reference: Option<String>,
+        #[arg(long, conflicts_with_all = ["notes_file", "changelog"])]
+        notes: Option<String>,
+        #[arg(long, conflicts_with = "changelog")]
+        notes_file: Option<PathBuf>,
+        /// use the section of this changelog that mentions the tag
+        #[arg(long)]
+        changelog: Option<PathBuf>,
+        /// asset link as name=url, can be repeated
+        #[arg(short, long = "asset")]
+        assets: Vec<String>,
+        /// milestone title, can be repeated
+        #[arg(short, long = "milestone")]
+        milestones: Vec<String>,
+        /// attach artifact links from the successful jobs of this pipeline
+        #[arg(long)]
+        from_pipeline: Option<i64>,
+    },
+    // labtool releases update --project testproject --tag v1.2.0 --notes-file notes.md
+    Update {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        tag: String,
+        #[arg(short, long)]
+        name: Option<String>,
+        #[arg(long, conflicts_with_all = ["notes_file", "changelog"])]
+        notes: Option<String>,
+        #[arg(long, conflicts_with = "changelog")]
+        notes_file: Option<PathBuf>,
+        #[arg(long)]
+        changelog: Option<PathBuf>,
+        #[arg(short, long = "asset")]
+        assets: Vec<String>,
+        #[arg(short, long = "milestone")]
+        milestones: Vec<String>,
+    },
+    /// delete a release, keeping its tag
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        tag: String,
+    },
+}
+#[derive(Subcommand)]
+pub enum ScheduleActions {
+    // labtool schedules list --project testproject
+    /// list schedules with their next run and last pipeline status
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+    // labtool schedules create --project testproject --description nightly --cron "0 2 * * *" --ref main --variable DEPLOY=false
+    Create {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        description: String,
+        #[arg(short, long)]
+        cron: String,
+        #[arg(short, long, default_value = "UTC")]
+        timezone: String,
+        #[arg(short, long = "ref")]
+        reference: String,
+        #[arg(short, long, default_value_t = true, action = clap::ArgAction::Set)]
+        active: bool,
+        /// schedule variable as KEY=VALUE, can be repeated
+        #[arg(long = "variable")]
+        variables: Vec<String>,
+    },
+    // labtool schedules update --project testproject --id 12 --cron "0 3 * * *" --active false
+    Update {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        id: u64,
+        #[arg(short, long)]
+        description: Option<String>,
+        #[arg(short, long)]
+        cron: Option<String>,
+        #[arg(short, long)]
+        timezone: Option<String>,
+        #[arg(short, long = "ref")]
+        reference: Option<String>,
+        #[arg(short, long)]
+        active: Option<bool>,
+        #[arg(long = "variable")]
+        variables: Vec<String>,
+    },
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        id: u64,
+    },
+    // labtool schedules run --project testproject --id 12
+    /// run a schedule now and print the pipeline it starts
+    Run {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        id: u64,
+    },
+}
+#[derive(Subcommand)]
+pub enum EnvironmentActions {
+    // labtool environments list --project testproject --state available
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        /// available, stopping or stopped
+        #[arg(short, long)]
+        state: Option<String>,
+    },
+    // labtool environments view --project testproject --name production
+    /// show an environment and its last deployment
+    View {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+    },
+    /// stop an environment, running its on_stop job
+    Stop {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+    },
+    /// delete a stopped environment
+    Delete {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        name: String,
+    },
+}
+#[derive(Subcommand)]
+pub enum DeploymentActions {
+    // labtool deployments list --project testproject --environment production
+    /// list deployments to an environment, newest first
+    List {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        environment: String,
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+    // labtool deployments rollback --project testproject --environment production --to 123456
+    /// re-run the deploy job of an earlier deployment
+    Rollback {
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long)]
+        environment: String,
+        #[arg(short, long = "to")]
+        deployment_id: u64,
+    },
+}
+#[derive(Subcommand)]
+pub enum RunnerActions {
+    // labtool runners list --project testproject | --group my-group | --all --details
+    /// list runners, your own ones when no scope is given
+    List {
+        #[arg(short, long, conflicts_with_all = ["group", "all"])]
+        project: Option<String>,
+        #[arg(short, long, conflicts_with = "all")]
+        group: Option<String>,
+        /// every runner on the instance, requires an administrator token
+        #[arg(short, long, default_value_t = false)]
+        all: bool,
+        /// also show tags, version and last contact, one extra request per runner
+        #[arg(long, default_value_t = false)]
+        details: bool,
+    },
+    // labtool runners view --id 12345 --limit 10
+    /// show a runner and the jobs it ran recently
+    View {
+        #[arg(short, long)]
+        id: u64,
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// stop a runner from picking up new jobs
+    Pause {
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// let a paused runner pick up jobs again
+    Resume {
+        #[arg(short, long)]
+        id: u64,
+    },
+    Delete {
+        #[arg(short, long)]
+        id: u64,
+    },
+}
+// Which project or group a members command operates on,
+// defaulting to the project of the current git repository
+#[derive(Args)]
+#[group(multiple = false)]
+pub struct MemberTarget {
+    #[arg(short, long)]
+    pub project: Option<String>,
+    #[arg(short, long)]
+    pub group: Option<String>,
+}
+#[derive(Subcommand)]
+pub enum MemberActions {
+    // labtool members list --project testproject --all
+    List {
+        #[command(flatten)]
+        target: MemberTarget,
+        /// include members inherited from parent groups
+        #[arg(short, long, default_value_t = false)]
+        all: bool,
+    },
+    // labtool members add --group my-group --user jdoe --access-level developer --expires-at 2025-06-30
+    Add {
+        #[command(flatten)]
+        target: MemberTarget,
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long, value_enum)]
+        access_level: AccessLevel,
+        /// YYYY-MM-DD
+        #[arg(short, long)]
+        expires_at: Option<String>,
+    },
+    // labtool members update --project testproject --user jdoe --access-level maintainer
+    Update {
+        #[command(flatten)]
+        target: MemberTarget,
+        #[arg(short, long)]
+        user: String,
+        #[arg(short, long, value_enum)]
+        access_level: Option<AccessLevel>,
+        /// YYYY-MM-DD
+        #[arg(short, long)]
+        expires_at: Option<String>,
+    },
+    Remove {
+        #[command(flatten)]
+        target: MemberTarget,
+        #[arg(short, long)]
+        user: String,
+    },
+    // labtool members audit --user jdoe
+    /// show the projects and groups a user is a member of
+    Audit {
+        #[arg(short, long)]
+        user: String,
+    },
+}
+#[derive(ValueEnum, Clone, Copy)]
+pub enum AccessLevel {
+    Guest,
+    Reporter,
+    Developer,
+    Maintainer,
+    Owner,
+}
+impl AccessLevel {
+    pub fn level(self) -> u64 {
+        match self {
+            AccessLevel::Guest => 10,
+            AccessLevel::Reporter => 20,
+            AccessLevel::Developer => 30,
+            AccessLevel::Maintainer => 40,
+            AccessLevel::Owner => 50,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ProjectSort {
+    #[value(name = "last_activity")]
+    LastActivity,
+    Name,
+    Created,
+}
+impl ProjectSort {
+    // The `order_by` and `sort` query values, names read best ascending
+    pub fn order(self) -> (&'static str, &'static str) {
+        match self {
+            ProjectSort::LastActivity => ("last_activity_at", "desc"),
+            ProjectSort::Name => ("name", "asc"),
+            ProjectSort::Created => ("created_at", "desc"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum CacheActions {
+    // labtool cache clear
+    /// remove cached project IDs and API responses
+    Clear {},
+}
+
+#[derive(Subcommand)]
+pub enum CiActions {
+    // labtool ci lint --file .gitlab-ci.yml --project testproject --ref main --dry-run-pipeline
+    /// validate a local CI file with the project's CI lint API
+    Lint {
+        #[arg(short, long, default_value = ".gitlab-ci.yml")]
+        file: PathBuf,
+        #[arg(short, long)]
+        project: Option<String>,
+        #[arg(short, long = "ref")]
+        reference: Option<String>,
+        /// simulate pipeline creation on the ref to check rules and needs
+        #[arg(long, default_value_t = false)]
+        dry_run_pipeline: bool,
+        /// print the configuration with all includes expanded
+        #[arg(long, default_value_t = false)]
+        show_merged: bool,
+    },
+    // labtool ci run-local build --container docker
+    /// run one job's script locally using the project's CI variables
+    RunLocal {
+        job: String,
+        #[arg(short, long, default_value = ".gitlab-ci.yml")]
+        file: PathBuf,
+        #[arg(short, long)]
+        project: Option<String>,
+        /// also pass masked variables to the job
+        #[arg(long, default_value_t = false)]
+        include_masked: bool,
+        /// run in the job's image with this container runtime
+        #[arg(long, num_args = 0..=1, default_missing_value = "docker")]
+        container: Option<String>,
+        /// shell that runs the script, on the host or in the container
+        #[arg(long, default_value = "sh")]
+        shell: String,
+        /// show the resolved script and variables without running anything
+        #[arg(long, default_value_t = false)]
+        print_only: bool,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    Ascii,
+    Dot,
+    Mermaid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    // Global flags are added to every subcommand, so their names must not clash with any of them
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
 }
@@ -0,0 +1,36 @@
+use std::error::Error;
+use std::io::{self, IsTerminal, Write};
+
+use crate::client::is_dry_run;
+
+// Ask the user to type DELETE before a destructive action goes ahead.
+// `--yes` skips the prompt, and without it we refuse outright when nobody is
+// at the terminal rather than blocking on stdin forever (e.g. in CI).
+// Nothing is deleted under --dry-run, so there is nothing to confirm.
+pub fn confirm_destructive(action: &str, assume_yes: bool) -> Result<bool, Box<dyn Error>> {
+    confirm_action(action, "DELETE", assume_yes)
+}
+
+// Like `confirm_destructive`, for actions that change rather than delete
+// something and so ask for a word that matches them
+pub fn confirm_action(action: &str, keyword: &str, assume_yes: bool) -> Result<bool, Box<dyn Error>> {
+    if assume_yes || is_dry_run() {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(Box::from(format!(
+            "Refusing to {} without confirmation: stdin is not a terminal, pass --yes to proceed",
+            action
+        )));
+    }
+
+    print!("Are you sure you want to {}? Type '{}' to confirm: ", action, keyword);
+    io::stdout().flush()?;
+    let mut confirmation = String::new();
+    io::stdin().read_line(&mut confirmation)?;
+    if confirmation.trim() != keyword {
+        println!("Operation aborted.");
+        return Ok(false);
+    }
+    Ok(true)
+}
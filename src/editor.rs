@@ -0,0 +1,44 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::Command;
+use std::{env, fs};
+
+// Resolve a description given inline, from a file, or written in $EDITOR
+pub fn read_description(text: Option<String>, file: Option<PathBuf>, use_editor: bool) -> Result<Option<String>, Box<dyn Error>> {
+    if let Some(text) = text {
+        return Ok(Some(text));
+    }
+    if let Some(path) = file {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return Ok(Some(contents));
+    }
+    if use_editor {
+        return open_editor().map(Some);
+    }
+    Ok(None)
+}
+
+fn open_editor() -> Result<String, Box<dyn Error>> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from("vi"));
+    let path = env::temp_dir().join(format!("labtool-{}.md", std::process::id()));
+    fs::write(&path, "")?;
+
+    // $EDITOR may carry its own arguments, e.g. `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().ok_or("$EDITOR is empty")?;
+    let status = Command::new(program).args(parts).arg(&path).status()?;
+    if !status.success() {
+        let _ = fs::remove_file(&path);
+        return Err(Box::from(format!("Editor `{}` exited with {}", editor, status)));
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    fs::remove_file(&path)?;
+    if contents.trim().is_empty() {
+        return Err(Box::from("Aborting: the description is empty"));
+    }
+    Ok(contents)
+}
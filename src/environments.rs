@@ -0,0 +1,256 @@
+use std::error::Error;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::Value;
+
+use crate::client::{fetch_all_pages_with_query, API_CLIENT};
+use crate::confirm::{confirm_action, confirm_destructive};
+use crate::pipelines::{colorize_status, play_job, retry_job};
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_environments(token: &str, project_name: &str, state: Option<String>) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let url = format!("https://gitlab.com/api/v4/projects/{}/environments", project_id);
+    let query: Vec<(&str, &str)> = state.as_deref().map(|state| ("states", state)).into_iter().collect();
+    let environments = fetch_all_pages_with_query(token, &url, &query)?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").add_attribute(Attribute::Bold),
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("State").add_attribute(Attribute::Bold),
+            Cell::new("Tier").add_attribute(Attribute::Bold),
+            Cell::new("External URL").add_attribute(Attribute::Bold),
+            Cell::new("Updated At").add_attribute(Attribute::Bold),
+        ]);
+
+    for environment in environments {
+        let state = environment["state"].as_str().unwrap_or("");
+        let state_cell = if state == "available" {
+            Cell::new(state).fg(Color::Green)
+        } else {
+            Cell::new(state).fg(Color::DarkGrey)
+        };
+        table.add_row(vec![
+            Cell::new(environment["id"].as_u64().unwrap_or_default().to_string()),
+            Cell::new(environment["name"].as_str().unwrap_or("")),
+            state_cell,
+            Cell::new(environment["tier"].as_str().unwrap_or("")),
+            Cell::new(environment["external_url"].as_str().unwrap_or("")),
+            Cell::new(environment["updated_at"].as_str().unwrap_or("")),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn view_environment(token: &str, project_name: &str, environment_name: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let environment_id = fetch_environment_id(token, project_id, environment_name)?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/environments/{}", project_id, environment_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch environment {}: {}", environment_name, response.status());
+        return Err(Box::from(error_message));
+    }
+    let environment: Value = response.json()?;
+
+    println!("{} [{}]", environment["name"].as_str().unwrap_or("").bold(), environment["state"].as_str().unwrap_or(""));
+    println!("Tier: {}", environment["tier"].as_str().unwrap_or("-"));
+    if let Some(url) = environment["external_url"].as_str() {
+        println!("External URL \"{}\"", url);
+    }
+    if let Some(auto_stop_at) = environment["auto_stop_at"].as_str() {
+        println!("Auto stops at \"{}\"", auto_stop_at);
+    }
+
+    let deployment = &environment["last_deployment"];
+    if deployment.is_null() {
+        println!("\nNever deployed.");
+    } else {
+        println!("\n{}", "Last deployment".bold());
+        println!("Deployment ID {}: {}", deployment["id"], deployment["status"].as_str().unwrap_or(""));
+        println!(
+            "Deployed {} from \"{}\" by @{} at \"{}\"",
+            deployment["sha"].as_str().unwrap_or("").chars().take(8).collect::<String>(),
+            deployment["ref"].as_str().unwrap_or(""),
+            deployment["user"]["username"].as_str().unwrap_or(""),
+            deployment["created_at"].as_str().unwrap_or("")
+        );
+        let pipeline = &deployment["deployable"]["pipeline"];
+        if !pipeline.is_null() {
+            println!("Pipeline ID {}: {}", pipeline["id"], colorize_status(pipeline["status"].as_str().unwrap_or("")));
+        }
+    }
+    Ok(())
+}
+
+pub async fn stop_environment(token: &str, project_name: &str, environment_name: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("stop environment `{}` in project `{}`", environment_name, project_name);
+    if !confirm_action(&action, "STOP", assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let environment_id = fetch_environment_id(token, project_id, environment_name)?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/environments/{}/stop", project_id, environment_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Environment `{}` is stopping.", environment_name);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to stop environment: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_environment(token: &str, project_name: &str, environment_name: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete environment `{}` in project `{}`", environment_name, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let environment_id = fetch_environment_id(token, project_id, environment_name)?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/environments/{}", project_id, environment_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Environment `{}` deleted successfully.", environment_name);
+        Ok(())
+    } else {
+        // GitLab only deletes environments that have been stopped
+        let error_message = format!("Failed to delete environment (is it stopped?): {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn list_deployments(token: &str, project_name: &str, environment: &str, limit: usize) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/deployments", project_id))
+        .query(&[
+            ("environment", environment),
+            ("order_by", "created_at"),
+            ("sort", "desc"),
+            ("per_page", &limit.to_string()),
+        ])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch deployments: {}", response.status());
+        return Err(Box::from(error_message));
+    }
+    let deployments: Vec<Value> = response.json()?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").add_attribute(Attribute::Bold),
+            Cell::new("SHA").add_attribute(Attribute::Bold),
+            Cell::new("Ref").add_attribute(Attribute::Bold),
+            Cell::new("Deployed By").add_attribute(Attribute::Bold),
+            Cell::new("Deployed At").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+            Cell::new("Pipeline").add_attribute(Attribute::Bold),
+        ]);
+
+    for deployment in deployments {
+        let status = deployment["status"].as_str().unwrap_or("");
+        let status_cell = match status {
+            "success" => Cell::new(status).fg(Color::Green),
+            "failed" | "canceled" => Cell::new(status).fg(Color::Red),
+            _ => Cell::new(status),
+        };
+        let pipeline = &deployment["deployable"]["pipeline"];
+        table.add_row(vec![
+            Cell::new(deployment["id"].as_u64().unwrap_or_default().to_string()),
+            Cell::new(deployment["sha"].as_str().unwrap_or("").chars().take(8).collect::<String>()),
+            Cell::new(deployment["ref"].as_str().unwrap_or("")),
+            Cell::new(deployment["user"]["username"].as_str().unwrap_or("")),
+            Cell::new(deployment["created_at"].as_str().unwrap_or("")),
+            status_cell,
+            Cell::new(format!("{} {}", pipeline["id"], pipeline["status"].as_str().unwrap_or(""))),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+// Redeploy an earlier deployment by re-running the job that produced it
+pub async fn rollback_deployment(token: &str, project_name: &str, environment: &str, deployment_id: u64, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/deployments/{}", project_id, deployment_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch deployment {}: {}", deployment_id, response.status());
+        return Err(Box::from(error_message));
+    }
+    let deployment: Value = response.json()?;
+
+    let deployed_to = deployment["environment"]["name"].as_str().unwrap_or("");
+    if deployed_to != environment {
+        return Err(Box::from(format!(
+            "Deployment {} belongs to environment `{}`, not `{}`",
+            deployment_id, deployed_to, environment
+        )));
+    }
+    let job_id = deployment["deployable"]["id"]
+        .as_i64()
+        .ok_or_else(|| format!("Deployment {} has no deploy job to re-run", deployment_id))?;
+
+    let action = format!(
+        "roll back environment `{}` in project `{}` to {} ({})",
+        environment,
+        project_name,
+        deployment["sha"].as_str().unwrap_or("").chars().take(8).collect::<String>(),
+        deployment["ref"].as_str().unwrap_or("")
+    );
+    if !confirm_action(&action, "ROLLBACK", assume_yes)? {
+        return Ok(());
+    }
+
+    // Manual deploy jobs that never ran are played, everything else is retried
+    let job = if deployment["deployable"]["status"].as_str() == Some("manual") {
+        play_job(token, project_id, job_id)?
+    } else {
+        retry_job(token, project_id, job_id)?
+    };
+
+    println!(
+        "Rolling back `{}` to {} ({}) with job {}",
+        environment,
+        deployment["sha"].as_str().unwrap_or("").chars().take(8).collect::<String>(),
+        deployment["ref"].as_str().unwrap_or(""),
+        job["id"]
+    );
+    println!("Web URL \"{}\"", job["web_url"].as_str().unwrap_or(""));
+    Ok(())
+}
+
+fn fetch_environment_id(token: &str, project_id: u64, environment_name: &str) -> Result<u64, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/environments", project_id))
+        .query(&[("name", environment_name)])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to look up environment '{}': {}", environment_name, response.status())));
+    }
+    let environments: Vec<Value> = response.json()?;
+    environments
+        .first()
+        .and_then(|environment| environment["id"].as_u64())
+        .ok_or_else(|| Box::from(format!("Environment '{}' not found", environment_name)))
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Only remotes on the host the API calls go to can be turned into projects
+const GITLAB_HOST: &str = "gitlab.com";
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_verbose(enabled: bool) {
+    VERBOSE.store(enabled, Ordering::Relaxed);
+}
+
+// Use the given project, or the one behind the `origin` remote of the current repository
+pub fn resolve_project(project: &Option<String>) -> Result<String, Box<dyn Error>> {
+    if let Some(project) = project {
+        return Ok(project.clone());
+    }
+    let project = remote_project().ok_or_else(|| {
+        format!("No --project given and the current directory has no `origin` remote on {}", GITLAB_HOST)
+    })?;
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("Using project `{}` from the origin remote", project);
+    }
+    Ok(project)
+}
+
+// Default to the checked out branch, but only when `project` is the current repository
+pub fn resolve_branch(branch: &Option<String>, project: &str) -> Option<String> {
+    if branch.is_some() {
+        return branch.clone();
+    }
+    let remote = remote_project()?;
+    if remote != project && !remote.ends_with(&format!("/{}", project)) {
+        return None;
+    }
+    // Fails on a detached HEAD, which has no branch to default to
+    let branch = git(&["symbolic-ref", "--short", "HEAD"])?;
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("Using branch `{}` from the current checkout", branch);
+    }
+    Some(branch)
+}
+
+fn remote_project() -> Option<String> {
+    let url = git(&["remote", "get-url", "origin"])?;
+    let (host, path) = parse_remote_url(&url)?;
+    if !host.eq_ignore_ascii_case(GITLAB_HOST) {
+        return None;
+    }
+    Some(path)
+}
+
+// Split `git@host:group/project.git`, `ssh://git@host:22/group/project.git` and
+// `https://user@host/group/project.git` into the host and the project path
+fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            let host = host.split(':').next()?;
+            (host, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some((host.to_string(), path.to_string()))
+}
+
+// Trimmed stdout of a successful git command, None when it fails or prints nothing
+pub fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(url: &str) -> Option<(String, String)> {
+        parse_remote_url(url)
+    }
+
+    #[test]
+    fn parses_scp_like_remotes() {
+        assert_eq!(
+            parsed("git@gitlab.com:group/project.git"),
+            Some(("gitlab.com".to_string(), "group/project".to_string()))
+        );
+        assert_eq!(
+            parsed("gitlab.com:group/sub/project"),
+            Some(("gitlab.com".to_string(), "group/sub/project".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_url_remotes() {
+        assert_eq!(
+            parsed("ssh://git@gitlab.com:22/group/project.git"),
+            Some(("gitlab.com".to_string(), "group/project".to_string()))
+        );
+        assert_eq!(
+            parsed("https://user@gitlab.com/group/sub/project.git/"),
+            Some(("gitlab.com".to_string(), "group/sub/project".to_string()))
+        );
+        assert_eq!(
+            parsed("https://github.com/owner/repo"),
+            Some(("github.com".to_string(), "owner/repo".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_paths_without_a_namespace() {
+        assert_eq!(parsed("https://gitlab.com/project.git"), None);
+        assert_eq!(parsed("/srv/git/project.git"), None);
+        assert_eq!(parsed("project"), None);
+    }
+}
@@ -1,5 +1,4 @@
 use std::{error::Error, sync::Mutex, fs, io};
-use std::path::PathBuf;
 use lazy_static::lazy_static;
 
 const TOKEN_FILE: &str = ".gitlab_token";
@@ -0,0 +1,286 @@
+use std::error::Error;
+use std::path::PathBuf;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, Map, Value};
+
+use crate::client::{fetch_all_pages, API_CLIENT};
+use crate::editor::read_description;
+use crate::projects::fetch_project_id_by_name;
+use crate::users::fetch_user_id_by_username;
+
+pub async fn list_issues(
+    token: &str,
+    project_name: &str,
+    labels: Option<Vec<String>>,
+    milestone: Option<String>,
+    assignee: Option<String>,
+    state: Option<String>,
+    search: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut query: Vec<(&str, String)> = vec![("per_page", String::from("100"))];
+    query.push(("state", state.unwrap_or_else(|| String::from("opened"))));
+    if let Some(labels) = labels {
+        query.push(("labels", labels.join(",")));
+    }
+    if let Some(milestone) = milestone {
+        query.push(("milestone", milestone));
+    }
+    if let Some(assignee) = assignee {
+        query.push(("assignee_username", assignee));
+    }
+    if let Some(search) = search {
+        query.push(("search", search));
+    }
+
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/issues", project_id))
+        .query(&query)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        let issues: Vec<Value> = response.json()?;
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("IID").add_attribute(Attribute::Bold),
+                Cell::new("Title").add_attribute(Attribute::Bold),
+                Cell::new("State").add_attribute(Attribute::Bold),
+                Cell::new("Assignees").add_attribute(Attribute::Bold),
+                Cell::new("Labels").add_attribute(Attribute::Bold),
+                Cell::new("Milestone").add_attribute(Attribute::Bold),
+                Cell::new("Updated At").add_attribute(Attribute::Bold),
+            ]);
+
+        for issue in issues {
+            let state = issue["state"].as_str().unwrap_or("");
+            let state_cell = if state == "opened" {
+                Cell::new(state).fg(Color::Green)
+            } else {
+                Cell::new(state).fg(Color::Red)
+            };
+            table.add_row(vec![
+                Cell::new(format!("#{}", issue["iid"].as_u64().unwrap_or_default())),
+                Cell::new(issue["title"].as_str().unwrap_or("")),
+                state_cell,
+                Cell::new(join_usernames(&issue["assignees"])),
+                Cell::new(join_labels(&issue["labels"])),
+                Cell::new(issue["milestone"]["title"].as_str().unwrap_or("")),
+                Cell::new(issue["updated_at"].as_str().unwrap_or("")),
+            ]);
+        }
+        println!("{table}");
+        Ok(())
+    } else {
+        let error_message = format!("Failed to fetch issues: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn view_issue(token: &str, project_name: &str, iid: u64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let issue_url = format!("https://gitlab.com/api/v4/projects/{}/issues/{}", project_id, iid);
+
+    let response = API_CLIENT
+        .get(&issue_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch issue #{}: {}", iid, response.status());
+        return Err(Box::from(error_message));
+    }
+    let issue: Value = response.json()?;
+
+    let state = issue["state"].as_str().unwrap_or("");
+    let state = if state == "opened" { state.green() } else { state.red() };
+    println!("{} {}  [{}]", format!("#{}", iid).bold(), issue["title"].as_str().unwrap_or("").bold(), state);
+    println!("Author: @{}   Created at \"{}\"", issue["author"]["username"].as_str().unwrap_or(""), issue["created_at"].as_str().unwrap_or(""));
+    if issue["assignees"].as_array().is_some_and(|assignees| !assignees.is_empty()) {
+        println!("Assignees: {}", join_usernames(&issue["assignees"]));
+    }
+    if issue["labels"].as_array().is_some_and(|labels| !labels.is_empty()) {
+        println!("Labels: {}", join_labels(&issue["labels"]));
+    }
+    if let Some(milestone) = issue["milestone"]["title"].as_str() {
+        println!("Milestone: {}", milestone);
+    }
+    println!("Web URL \"{}\"", issue["web_url"].as_str().unwrap_or(""));
+
+    println!("\n{}", "Description".bold());
+    match issue["description"].as_str().filter(|text| !text.trim().is_empty()) {
+        Some(description) => println!("{}", description),
+        None => println!("{}", "No description provided.".dimmed()),
+    }
+
+    let notes = fetch_all_pages(token, &format!("{}/notes?sort=asc&order_by=created_at", issue_url))?;
+    let comments: Vec<&Value> = notes
+        .iter()
+        .filter(|note| !note["system"].as_bool().unwrap_or(false))
+        .collect();
+    println!("\n{}", format!("Comments ({})", comments.len()).bold());
+    for note in comments {
+        println!(
+            "{} {}",
+            format!("@{}", note["author"]["username"].as_str().unwrap_or("")).bold(),
+            note["created_at"].as_str().unwrap_or("").dimmed()
+        );
+        for line in note["body"].as_str().unwrap_or("").lines() {
+            println!("  {}", line);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_issue(
+    token: &str,
+    project_name: &str,
+    title: &str,
+    description: Option<String>,
+    description_file: Option<PathBuf>,
+    use_editor: bool,
+    labels: Option<Vec<String>>,
+    assignee: Option<String>,
+    milestone: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let description = read_description(description, description_file, use_editor)?;
+
+    let mut body = Map::new();
+    body.insert(String::from("title"), json!(title));
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    if let Some(labels) = labels {
+        body.insert(String::from("labels"), json!(labels.join(",")));
+    }
+    if let Some(assignee) = assignee {
+        let assignee_id = fetch_user_id_by_username(token, &assignee).await?;
+        body.insert(String::from("assignee_ids"), json!([assignee_id]));
+    }
+    if let Some(milestone) = milestone {
+        let milestone_id = fetch_milestone_id(token, project_id, &milestone)?;
+        body.insert(String::from("milestone_id"), json!(milestone_id));
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/issues", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        let issue: Value = response.json()?;
+        println!("Issue #{} was created successfully", issue["iid"].as_u64().unwrap_or_default());
+        println!("Web URL \"{}\"", issue["web_url"].as_str().unwrap_or(""));
+        Ok(())
+    } else {
+        let error_message = format!("Failed to create issue: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+// Close or reopen an issue, `state_event` is either "close" or "reopen"
+pub async fn set_issue_state(token: &str, project_name: &str, iid: u64, state_event: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    update_issue(token, project_id, iid, &json!({ "state_event": state_event }))?;
+    let state = if state_event == "close" { "closed" } else { "reopened" };
+    println!("Issue #{} was {} successfully", iid, state);
+    Ok(())
+}
+
+pub async fn comment_on_issue(
+    token: &str,
+    project_name: &str,
+    iid: u64,
+    body: Option<String>,
+    body_file: Option<PathBuf>,
+    use_editor: bool,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let body = read_description(body, body_file, use_editor)?
+        .ok_or("A comment body is required, pass --body, --body-file or --editor")?;
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/issues/{}/notes", project_id, iid))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "body": body }))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Comment added to issue #{}.", iid);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to comment on issue #{}: {}", iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn add_issue_labels(token: &str, project_name: &str, iid: u64, labels: &[String]) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    update_issue(token, project_id, iid, &json!({ "add_labels": labels.join(",") }))?;
+    println!("Added label(s) {} to issue #{}", labels.join(", "), iid);
+    Ok(())
+}
+
+pub async fn remove_issue_labels(token: &str, project_name: &str, iid: u64, labels: &[String]) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    update_issue(token, project_id, iid, &json!({ "remove_labels": labels.join(",") }))?;
+    println!("Removed label(s) {} from issue #{}", labels.join(", "), iid);
+    Ok(())
+}
+
+fn update_issue(token: &str, project_id: u64, iid: u64, body: &Value) -> Result<(), Box<dyn Error>> {
+    let response = API_CLIENT
+        .put(format!("https://gitlab.com/api/v4/projects/{}/issues/{}", project_id, iid))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(body)
+        .send()?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let error_message = format!("Failed to update issue #{}: {}", iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+fn fetch_milestone_id(token: &str, project_id: u64, title: &str) -> Result<u64, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/milestones", project_id))
+        .query(&[("title", title)])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to look up milestone '{}': {}", title, response.status())));
+    }
+    let milestones: Vec<Value> = response.json()?;
+    milestones
+        .first()
+        .and_then(|milestone| milestone["id"].as_u64())
+        .ok_or_else(|| Box::from(format!("Milestone '{}' not found", title)))
+}
+
+fn join_usernames(users: &Value) -> String {
+    users
+        .as_array()
+        .map(|users| users.iter()
+            .filter_map(|user| user["username"].as_str())
+            .map(|username| format!("@{}", username))
+            .collect::<Vec<String>>()
+            .join(", "))
+        .unwrap_or_default()
+}
+
+fn join_labels(labels: &Value) -> String {
+    labels
+        .as_array()
+        .map(|labels| labels.iter().filter_map(Value::as_str).collect::<Vec<&str>>().join(", "))
+        .unwrap_or_default()
+}
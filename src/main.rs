@@ -4,50 +4,133 @@ mod gitlab_login;
 mod pipelines;
 mod client;
 mod variables;
+mod confirm;
+mod branches;
+mod editor;
+mod issues;
+mod merge_requests;
+mod users;
+mod tags;
+mod releases;
+mod schedules;
+mod environments;
+mod runners;
+mod members;
+mod scope;
+mod cache;
+mod git_remote;
+mod ci;
+mod pipeline_graph;
+mod pipeline_failures;
+mod pipeline_stats;
+mod pipeline_tests;
+mod pipeline_coverage;
 
-use std::io::Read;
-use serde_json::{json, Value};
-use clap::{Parser, Subcommand};
-use lazy_static::lazy_static;
+use clap::Parser;
 use std::{error::Error, sync::Mutex};
 use commands::Commands;
-use crate::commands::{JobAction, PipelineActions, ProjectActions, VariablesActions};
+use crate::commands::{BranchActions, CacheActions, CiActions, DeploymentActions, EnvironmentActions, IssueActions, IssueLabelAction, JobAction, MemberActions, MergeRequestActions, PipelineActions, ProjectActions, ProjectSettingsAction, ReleaseActions, RunnerActions, ScheduleActions, TagActions, VariablesActions};
+use crate::ci::{lint_ci_config, run_local_job};
+use crate::client::{set_dry_run, DryRun};
+use crate::git_remote::{resolve_branch, resolve_project, set_verbose};
 use crate::gitlab_login::{fetch_stored_token, login};
-use crate::pipelines::{fetch_job_logs, fetch_pipelines_for_project};
-use crate::projects::{fetch_all_gitlab_projects, fetch_project_by_name};
-use crate::variables::{delete_project_variables, list_project_variables, set_project_variables, update_project_variables};
+use crate::branches::{create_branch, delete_branch, delete_merged_branches, list_branches, protect_branch, unprotect_branch};
+use crate::environments::{delete_environment, list_deployments, list_environments, rollback_deployment, stop_environment, view_environment};
+use crate::issues::{add_issue_labels, comment_on_issue, create_issue, list_issues, remove_issue_labels, set_issue_state, view_issue};
+use crate::members::{add_member, audit_member, list_members, remove_member, update_member};
+use crate::merge_requests::{approve_merge_request, comment_on_merge_request, create_merge_request, list_merge_request_discussions, list_merge_requests, merge_merge_request, resolve_merge_request_discussion, set_merge_request_state, show_merge_request_diff, view_merge_request};
+use crate::pipeline_coverage::show_coverage_trend;
+use crate::pipeline_failures::explain_pipeline_failure;
+use crate::pipeline_graph::show_pipeline_graph;
+use crate::pipeline_stats::show_pipeline_stats;
+use crate::pipeline_tests::show_test_report;
+use crate::pipelines::{delete_pipeline, erase_job, fetch_job_logs, fetch_pipelines_for_project, play_job_by_project, retry_job_by_project, trigger_pipeline};
+use crate::projects::{create_project, delete_project, fetch_all_gitlab_projects, fetch_project_by_name, fork_project, get_project_settings, set_project_archived, set_project_settings, ProjectListFilters};
+use crate::releases::{create_release, delete_release, list_releases, update_release, view_release};
+use crate::runners::{delete_runner, list_runners, set_runner_paused, view_runner};
+use crate::scope::Scope;
+use crate::schedules::{create_schedule, delete_schedule, list_schedules, run_schedule, update_schedule};
+use crate::tags::{create_tag, delete_tag, list_tags};
+use crate::variables::{copy_variables, delete_project_variables, list_effective_variables, list_project_variables, set_project_variables, update_project_variables};
 lazy_static::lazy_static! {
     static ref TOKEN: Mutex<Option<String>> = Mutex::new(None);
 }
-const TOKEN_FILE: &str = ".gitlab_token";
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = commands::Cli::parse();
+    set_dry_run(cli.dry_run);
+    cache::set_enabled(!cli.no_cache);
+    set_verbose(cli.verbose);
     let runtime = tokio::runtime::Runtime::new()?;
 
-    runtime.block_on(async {
+    let result: Result<(), Box<dyn Error>> = runtime.block_on(async {
         match &cli.command {
             Commands::Login { token } => {
                 login(token)?;  // Ensure login is async
             }
+            Commands::Cache { action } => {
+                match action {
+                    CacheActions::Clear {} => {
+                        cache::clear()?;
+                    }
+                }
+            }
             Commands::Projects { action } => {
                 // Fetch the token from storage
                 match fetch_stored_token() {
                     Ok(Some(token)) => {
                         match action {
-                            ProjectActions::List {} => {
-                                fetch_all_gitlab_projects(&token.trim()).await?;
+                            ProjectActions::List { search, owned, membership, starred, archived, visibility, topic, language, sort, group, include_subgroups } => {
+                                let filters = ProjectListFilters {
+                                    search: search.clone(),
+                                    owned: *owned,
+                                    membership: *membership,
+                                    starred: *starred,
+                                    archived: *archived,
+                                    visibility: visibility.clone(),
+                                    topic: topic.clone(),
+                                    language: language.clone(),
+                                    sort: *sort,
+                                    group: group.clone(),
+                                    include_subgroups: *include_subgroups,
+                                };
+                                fetch_all_gitlab_projects(token.trim(), &filters).await?;
+                            }
+                            ProjectActions::View { name, output } => {
+                                fetch_project_by_name(token.trim(), name.clone(), *output).await?;
+                            }
+                            ProjectActions::Create { name, path, namespace, visibility, default_branch, description, template, template_project_id } => {
+                                create_project(token.trim(), name, path.clone(), namespace.clone(), visibility.clone(), default_branch.clone(), description.clone(), template.clone(), *template_project_id).await?;
+                            }
+                            ProjectActions::Fork { name, namespace, fork_name } => {
+                                fork_project(token.trim(), name, namespace.clone(), fork_name.clone()).await?;
+                            }
+                            ProjectActions::Archive { name } => {
+                                set_project_archived(token.trim(), name, "archive").await?;
                             }
-                            ProjectActions::View { name } => {
-                                fetch_project_by_name(&token.trim(), name.clone()).await?;
+                            ProjectActions::Unarchive { name } => {
+                                set_project_archived(token.trim(), name, "unarchive").await?;
+                            }
+                            ProjectActions::Delete { name } => {
+                                delete_project(token.trim(), name, cli.yes).await?;
+                            }
+                            ProjectActions::Settings { action } => {
+                                match action {
+                                    ProjectSettingsAction::Get { name } => {
+                                        get_project_settings(token.trim(), name).await?;
+                                    }
+                                    ProjectSettingsAction::Set { name, settings } => {
+                                        set_project_settings(token.trim(), name, settings).await?;
+                                    }
+                                }
                             }
                         }
                     }
                     Ok(None) => {
-                        println!("No token found. Please login first using 'devopscli login --token \"X\"'");
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
                     }
                     Err(e) => {
-                        println!("Error fetching token: {}", e);
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
                     }
                 }
             }
@@ -55,24 +138,36 @@ fn main() -> Result<(), Box<dyn Error>> {
                 match fetch_stored_token() {
                     Ok(Some(token)) => {
                         match action{
-                            VariablesActions::List { project_name } => {
-                                list_project_variables(&token.trim(), project_name).await?
+                            VariablesActions::List { target } => {
+                                let scope = Scope::from_args(target.project_name.clone(), target.group.clone(), target.instance)?;
+                                list_project_variables(token.trim(), &scope).await?
                             }
-                            VariablesActions::Set { project_name, key, value } =>{
-                                set_project_variables(&token.trim(), project_name, key, value).await?
+                            VariablesActions::Set { target, key, value } =>{
+                                let scope = Scope::from_args(target.project_name.clone(), target.group.clone(), target.instance)?;
+                                set_project_variables(token.trim(), &scope, key, value).await?
                             }
-                            VariablesActions::Update {project_name, key, value}=>{
-                                update_project_variables(&token.trim(), project_name, key, value).await?
+                            VariablesActions::Update {target, key, value}=>{
+                                let scope = Scope::from_args(target.project_name.clone(), target.group.clone(), target.instance)?;
+                                update_project_variables(token.trim(), &scope, key, value).await?
                             }
-                            VariablesActions::Delete {project_name, key}=>{
-                                delete_project_variables(&token.trim(), project_name, key).await?
+                            VariablesActions::Delete {target, key}=>{
+                                let scope = Scope::from_args(target.project_name.clone(), target.group.clone(), target.instance)?;
+                                delete_project_variables(token.trim(), &scope, key, cli.yes).await?
+                            }
+                            VariablesActions::Effective { project_name } => {
+                                list_effective_variables(token.trim(), &resolve_project(project_name)?).await?
+                            }
+                            VariablesActions::Copy { from, from_group, instance, to, to_group, to_instance, keys, overwrite } => {
+                                let source = Scope::from_args(from.clone(), from_group.clone(), *instance)?;
+                                let target = Scope::from_args(to.clone(), to_group.clone(), *to_instance)?;
+                                copy_variables(token.trim(), source, target, keys.clone(), *overwrite).await?
                             }
                         }
                     }Ok(None) => {
-                        println!("No token found. Please login first using 'devopscli login --token \"X\"'");
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
                     }
                     Err(e) => {
-                        println!("Error fetching token: {}", e);
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
                     }
                 }
             }
@@ -80,30 +175,407 @@ fn main() -> Result<(), Box<dyn Error>> {
                 match fetch_stored_token() {
                     Ok(Some(token)) => {
                         match action {
-                            PipelineActions::List { project, last, branch, status, show_jobs } => {
-                                fetch_pipelines_for_project(&token.trim(), project, last.clone(), branch.clone(), show_jobs.clone()).await?;
+                            PipelineActions::List { project, last, branch, status: _, show_jobs, depth, coverage } => {
+                                let project = resolve_project(project)?;
+                                let branch = resolve_branch(branch, &project);
+                                fetch_pipelines_for_project(token.trim(), &project, last.clone(), branch, *show_jobs, *depth, *coverage).await?;
                             }
                             PipelineActions::Trigger { project, branch } => {
-                                //TODO implement function to trigger pipeline
+                                let project = resolve_project(project)?;
+                                let branch = resolve_branch(branch, &project)
+                                    .ok_or("No --branch given and the current branch could not be detected")?;
+                                trigger_pipeline(token.trim(), &project, &branch).await?;
+                            }
+                            PipelineActions::Graph { project, pipeline_id, format } => {
+                                show_pipeline_graph(token.trim(), &resolve_project(project)?, *pipeline_id, *format).await?;
+                            }
+                            PipelineActions::Why { project, pipeline_id, latest, branch, lines } => {
+                                let project = resolve_project(project)?;
+                                let branch = if *latest {
+                                    Some(resolve_branch(branch, &project).ok_or("--latest needs --branch when the current branch cannot be detected")?)
+                                } else {
+                                    None
+                                };
+                                explain_pipeline_failure(token.trim(), &project, *pipeline_id, branch, *lines).await?;
+                            }
+                            PipelineActions::Stats { project, last, branch, output } => {
+                                show_pipeline_stats(token.trim(), &resolve_project(project)?, last, branch.clone(), *output).await?;
+                            }
+                            PipelineActions::Tests { project, pipeline_id, failed_only, compare } => {
+                                show_test_report(token.trim(), &resolve_project(project)?, *pipeline_id, *failed_only, *compare).await?;
+                            }
+                            PipelineActions::Coverage { project, last, branch, threshold } => {
+                                let project = resolve_project(project)?;
+                                // Guessing the default branch would gate on the wrong pipelines, e.g. on a detached HEAD in CI
+                                let branch = resolve_branch(branch, &project)
+                                    .ok_or("No --branch given and it cannot be inferred from the current checkout")?;
+                                show_coverage_trend(token.trim(), &project, last, &branch, *threshold).await?;
+                            }
+                            PipelineActions::Delete { project, pipeline_id } => {
+                                delete_pipeline(token.trim(), &resolve_project(project)?, *pipeline_id, cli.yes).await?;
                             }
                             PipelineActions::Jobs { action } => {
                                 match action {
                                     JobAction::Logs { project, job_id } => {
-                                        fetch_job_logs(&token.trim(), project, *job_id).await;
+                                        fetch_job_logs(token.trim(), &resolve_project(project)?, *job_id).await?;
+                                    }
+                                    JobAction::Retry { project, job_id } => {
+                                        retry_job_by_project(token.trim(), &resolve_project(project)?, *job_id).await?;
+                                    }
+                                    JobAction::Play { project, job_id } => {
+                                        play_job_by_project(token.trim(), &resolve_project(project)?, *job_id).await?;
+                                    }
+                                    JobAction::Erase { project, job_id } => {
+                                        erase_job(token.trim(), &resolve_project(project)?, *job_id, cli.yes).await?;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Ci { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            CiActions::Lint { file, project, reference, dry_run_pipeline, show_merged } => {
+                                lint_ci_config(token.trim(), &resolve_project(project)?, file, reference.clone(), *dry_run_pipeline, *show_merged).await?;
+                            }
+                            CiActions::RunLocal { job, file, project, include_masked, container, shell, print_only } => {
+                                run_local_job(token.trim(), &resolve_project(project)?, file, job, *include_masked, container.clone(), shell, *print_only).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::MergeRequests { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            MergeRequestActions::List { project, state, author, reviewer, labels, target_branch } => {
+                                list_merge_requests(token.trim(), &resolve_project(project)?, state.clone(), author.clone(), reviewer.clone(), labels.clone(), target_branch.clone()).await?;
+                            }
+                            MergeRequestActions::View { project, iid } => {
+                                view_merge_request(token.trim(), &resolve_project(project)?, *iid).await?;
+                            }
+                            MergeRequestActions::Create { project, source_branch, target_branch, title, description, description_file, editor, assignees } => {
+                                create_merge_request(token.trim(), &resolve_project(project)?, source_branch, target_branch.clone(), title, description.clone(), description_file.clone(), *editor, assignees.clone()).await?;
+                            }
+                            MergeRequestActions::Approve { project, iid } => {
+                                approve_merge_request(token.trim(), &resolve_project(project)?, *iid).await?;
+                            }
+                            MergeRequestActions::Merge { project, iid, when_pipeline_succeeds, squash, squash_message, remove_source_branch } => {
+                                merge_merge_request(token.trim(), &resolve_project(project)?, *iid, *when_pipeline_succeeds, *squash, squash_message.clone(), *remove_source_branch).await?;
+                            }
+                            MergeRequestActions::Close { project, iid } => {
+                                set_merge_request_state(token.trim(), &resolve_project(project)?, *iid, "close").await?;
+                            }
+                            MergeRequestActions::Reopen { project, iid } => {
+                                set_merge_request_state(token.trim(), &resolve_project(project)?, *iid, "reopen").await?;
+                            }
+                            MergeRequestActions::Comments { project, iid, unresolved } => {
+                                list_merge_request_discussions(token.trim(), &resolve_project(project)?, *iid, *unresolved).await?;
+                            }
+                            MergeRequestActions::Comment { project, iid, discussion, body, body_file, editor } => {
+                                comment_on_merge_request(token.trim(), &resolve_project(project)?, *iid, discussion.clone(), body.clone(), body_file.clone(), *editor).await?;
+                            }
+                            MergeRequestActions::Resolve { project, iid, discussion, unresolve } => {
+                                resolve_merge_request_discussion(token.trim(), &resolve_project(project)?, *iid, discussion, !*unresolve).await?;
+                            }
+                            MergeRequestActions::Diff { project, iid } => {
+                                show_merge_request_diff(token.trim(), &resolve_project(project)?, *iid).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Issues { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            IssueActions::List { project, labels, milestone, assignee, state, search } => {
+                                list_issues(token.trim(), &resolve_project(project)?, labels.clone(), milestone.clone(), assignee.clone(), state.clone(), search.clone()).await?;
+                            }
+                            IssueActions::View { project, iid } => {
+                                view_issue(token.trim(), &resolve_project(project)?, *iid).await?;
+                            }
+                            IssueActions::Create { project, title, description, description_file, editor, labels, assignee, milestone } => {
+                                create_issue(token.trim(), &resolve_project(project)?, title, description.clone(), description_file.clone(), *editor, labels.clone(), assignee.clone(), milestone.clone()).await?;
+                            }
+                            IssueActions::Close { project, iid } => {
+                                set_issue_state(token.trim(), &resolve_project(project)?, *iid, "close").await?;
+                            }
+                            IssueActions::Reopen { project, iid } => {
+                                set_issue_state(token.trim(), &resolve_project(project)?, *iid, "reopen").await?;
+                            }
+                            IssueActions::Comment { project, iid, body, body_file, editor } => {
+                                comment_on_issue(token.trim(), &resolve_project(project)?, *iid, body.clone(), body_file.clone(), *editor).await?;
+                            }
+                            IssueActions::Label { action } => {
+                                match action {
+                                    IssueLabelAction::Add { project, iid, labels } => {
+                                        add_issue_labels(token.trim(), &resolve_project(project)?, *iid, labels).await?;
+                                    }
+                                    IssueLabelAction::Remove { project, iid, labels } => {
+                                        remove_issue_labels(token.trim(), &resolve_project(project)?, *iid, labels).await?;
                                     }
                                 }
                             }
                         }
                     }
                     Ok(None) => {
-                        println!("No token found. Please login first using 'devopscli login --token \"X\"'");
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Branches { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            BranchActions::List { project, search } => {
+                                list_branches(token.trim(), &resolve_project(project)?, search.clone()).await?;
+                            }
+                            BranchActions::Create { project, name, reference } => {
+                                create_branch(token.trim(), &resolve_project(project)?, name, reference).await?;
+                            }
+                            BranchActions::Delete { project, name, merged } => {
+                                match name {
+                                    Some(name) if !*merged => delete_branch(token.trim(), &resolve_project(project)?, name, cli.yes).await?,
+                                    _ => delete_merged_branches(token.trim(), &resolve_project(project)?, cli.yes).await?,
+                                }
+                            }
+                            BranchActions::Protect { project, name, push, merge, allow_force_push, code_owner_approval } => {
+                                protect_branch(token.trim(), &resolve_project(project)?, name, push.level(), merge.level(), *allow_force_push, *code_owner_approval).await?;
+                            }
+                            BranchActions::Unprotect { project, name } => {
+                                unprotect_branch(token.trim(), &resolve_project(project)?, name).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Tags { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            TagActions::List { project, search } => {
+                                list_tags(token.trim(), &resolve_project(project)?, search.clone()).await?;
+                            }
+                            TagActions::Create { project, name, reference, message } => {
+                                create_tag(token.trim(), &resolve_project(project)?, name, reference, message.clone()).await?;
+                            }
+                            TagActions::Delete { project, name } => {
+                                delete_tag(token.trim(), &resolve_project(project)?, name, cli.yes).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Releases { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            ReleaseActions::List { project } => {
+                                list_releases(token.trim(), &resolve_project(project)?).await?;
+                            }
+                            ReleaseActions::View { project, tag } => {
+                                view_release(token.trim(), &resolve_project(project)?, tag).await?;
+                            }
+                            ReleaseActions::Create { project, tag, name, reference, notes, notes_file, changelog, assets, milestones, from_pipeline } => {
+                                create_release(token.trim(), &resolve_project(project)?, tag, name.clone(), reference.clone(), notes.clone(), notes_file.clone(), changelog.clone(), assets.clone(), milestones.clone(), *from_pipeline).await?;
+                            }
+                            ReleaseActions::Update { project, tag, name, notes, notes_file, changelog, assets, milestones } => {
+                                update_release(token.trim(), &resolve_project(project)?, tag, name.clone(), notes.clone(), notes_file.clone(), changelog.clone(), assets.clone(), milestones.clone()).await?;
+                            }
+                            ReleaseActions::Delete { project, tag } => {
+                                delete_release(token.trim(), &resolve_project(project)?, tag, cli.yes).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Schedules { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            ScheduleActions::List { project } => {
+                                list_schedules(token.trim(), &resolve_project(project)?).await?;
+                            }
+                            ScheduleActions::Create { project, description, cron, timezone, reference, active, variables } => {
+                                create_schedule(token.trim(), &resolve_project(project)?, description, cron, timezone, reference, *active, variables.clone()).await?;
+                            }
+                            ScheduleActions::Update { project, id, description, cron, timezone, reference, active, variables } => {
+                                update_schedule(token.trim(), &resolve_project(project)?, *id, description.clone(), cron.clone(), timezone.clone(), reference.clone(), *active, variables.clone()).await?;
+                            }
+                            ScheduleActions::Delete { project, id } => {
+                                delete_schedule(token.trim(), &resolve_project(project)?, *id, cli.yes).await?;
+                            }
+                            ScheduleActions::Run { project, id } => {
+                                run_schedule(token.trim(), &resolve_project(project)?, *id).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Environments { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            EnvironmentActions::List { project, state } => {
+                                list_environments(token.trim(), &resolve_project(project)?, state.clone()).await?;
+                            }
+                            EnvironmentActions::View { project, name } => {
+                                view_environment(token.trim(), &resolve_project(project)?, name).await?;
+                            }
+                            EnvironmentActions::Stop { project, name } => {
+                                stop_environment(token.trim(), &resolve_project(project)?, name, cli.yes).await?;
+                            }
+                            EnvironmentActions::Delete { project, name } => {
+                                delete_environment(token.trim(), &resolve_project(project)?, name, cli.yes).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Deployments { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            DeploymentActions::List { project, environment, limit } => {
+                                list_deployments(token.trim(), &resolve_project(project)?, environment, *limit).await?;
+                            }
+                            DeploymentActions::Rollback { project, environment, deployment_id } => {
+                                rollback_deployment(token.trim(), &resolve_project(project)?, environment, *deployment_id, cli.yes).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Runners { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            RunnerActions::List { project, group, all, details } => {
+                                list_runners(token.trim(), project.clone(), group.clone(), *all, *details).await?;
+                            }
+                            RunnerActions::View { id, limit } => {
+                                view_runner(token.trim(), *id, *limit).await?;
+                            }
+                            RunnerActions::Pause { id } => {
+                                set_runner_paused(token.trim(), *id, true).await?;
+                            }
+                            RunnerActions::Resume { id } => {
+                                set_runner_paused(token.trim(), *id, false).await?;
+                            }
+                            RunnerActions::Delete { id } => {
+                                delete_runner(token.trim(), *id, cli.yes).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
+                    }
+                    Err(e) => {
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
+                    }
+                }
+            }
+            Commands::Members { action } => {
+                match fetch_stored_token() {
+                    Ok(Some(token)) => {
+                        match action {
+                            MemberActions::List { target, all } => {
+                                let scope = Scope::from_args(target.project.clone(), target.group.clone(), false)?;
+                                list_members(token.trim(), &scope, *all).await?;
+                            }
+                            MemberActions::Add { target, user, access_level, expires_at } => {
+                                let scope = Scope::from_args(target.project.clone(), target.group.clone(), false)?;
+                                add_member(token.trim(), &scope, user, access_level.level(), expires_at.clone()).await?;
+                            }
+                            MemberActions::Update { target, user, access_level, expires_at } => {
+                                let scope = Scope::from_args(target.project.clone(), target.group.clone(), false)?;
+                                update_member(token.trim(), &scope, user, access_level.map(|level| level.level()), expires_at.clone()).await?;
+                            }
+                            MemberActions::Remove { target, user } => {
+                                let scope = Scope::from_args(target.project.clone(), target.group.clone(), false)?;
+                                remove_member(token.trim(), &scope, user, cli.yes).await?;
+                            }
+                            MemberActions::Audit { user } => {
+                                audit_member(token.trim(), user).await?;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        return Err(Box::from("No token found. Please login first using 'devopscli login --token \"X\"'"));
                     }
                     Err(e) => {
-                        println!("Error fetching token: {}", e);
+                        return Err(Box::from(format!("Error fetching token: {}", e)));
                     }
                 }
             }
         }
         Ok(()) // Return Ok for the async block
-    })
+    });
+    match result {
+        // The held back request was already printed
+        Err(e) if e.is::<DryRun>() => Ok(()),
+        result => result,
+    }
 }
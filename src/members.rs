@@ -0,0 +1,251 @@
+use std::error::Error;
+use chrono::NaiveDate;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use reqwest::StatusCode;
+use serde_json::{json, Map, Value};
+
+use crate::client::{fetch_all_pages, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::projects::fetch_member_projects;
+use crate::scope::Scope;
+use crate::users::fetch_user_id_by_username;
+
+pub async fn list_members(token: &str, scope: &Scope, include_inherited: bool) -> Result<(), Box<dyn Error>> {
+    let mut url = scope.resource_url(token, "members").await?;
+    if include_inherited {
+        url.push_str("/all");
+    }
+    let members = fetch_all_pages(token, &url)?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Username").add_attribute(Attribute::Bold),
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Access").add_attribute(Attribute::Bold),
+            Cell::new("Expires At").add_attribute(Attribute::Bold),
+            Cell::new("State").add_attribute(Attribute::Bold),
+        ]);
+
+    for member in members {
+        let state = member["state"].as_str().unwrap_or("");
+        let state_cell = if state == "active" {
+            Cell::new(state).fg(Color::Green)
+        } else {
+            Cell::new(state).fg(Color::DarkGrey)
+        };
+        table.add_row(vec![
+            Cell::new(member["username"].as_str().unwrap_or("")),
+            Cell::new(member["name"].as_str().unwrap_or("")),
+            Cell::new(access_level_name(member["access_level"].as_u64().unwrap_or_default())),
+            Cell::new(member["expires_at"].as_str().unwrap_or("never")),
+            state_cell,
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn add_member(
+    token: &str,
+    scope: &Scope,
+    username: &str,
+    access_level: u64,
+    expires_at: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let members_url = scope.resource_url(token, "members").await?;
+    let user_id = fetch_user_id_by_username(token, username).await?;
+
+    let mut body = Map::new();
+    body.insert(String::from("user_id"), json!(user_id));
+    body.insert(String::from("access_level"), json!(access_level));
+    if let Some(expires_at) = expires_at {
+        body.insert(String::from("expires_at"), json!(validate_expiry(&expires_at)?));
+    }
+
+    let response = API_CLIENT
+        .post(&members_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("@{} was added to {} as {}", username, scope.describe(), access_level_name(access_level));
+        Ok(())
+    } else {
+        let error_message = format!("Failed to add member: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn update_member(
+    token: &str,
+    scope: &Scope,
+    username: &str,
+    access_level: Option<u64>,
+    expires_at: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let members_url = scope.resource_url(token, "members").await?;
+    let user_id = fetch_user_id_by_username(token, username).await?;
+
+    let mut body = Map::new();
+    if let Some(access_level) = access_level {
+        body.insert(String::from("access_level"), json!(access_level));
+    }
+    if let Some(expires_at) = expires_at {
+        body.insert(String::from("expires_at"), json!(validate_expiry(&expires_at)?));
+    }
+    if body.is_empty() {
+        return Err(Box::from("Nothing to update, pass --access-level and/or --expires-at"));
+    }
+
+    let response = API_CLIENT
+        .put(format!("{}/{}", members_url, user_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("@{} was updated in {}", username, scope.describe());
+        Ok(())
+    } else {
+        let error_message = format!("Failed to update member: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn remove_member(token: &str, scope: &Scope, username: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("remove @{} from {}", username, scope.describe());
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let members_url = scope.resource_url(token, "members").await?;
+    let user_id = fetch_user_id_by_username(token, username).await?;
+
+    let response = API_CLIENT
+        .delete(format!("{}/{}", members_url, user_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("@{} was removed from {}", username, scope.describe());
+        Ok(())
+    } else {
+        let error_message = format!("Failed to remove member: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+// Show every project and group a user is a member of. The memberships
+// endpoint needs an administrator token, without one we fall back to checking
+// the user's effective access on each project the token can reach.
+pub async fn audit_member(token: &str, username: &str) -> Result<(), Box<dyn Error>> {
+    let user_id = fetch_user_id_by_username(token, username).await?;
+
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/users/{}/memberships", user_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    match response.status() {
+        StatusCode::FORBIDDEN => {
+            eprintln!("Listing memberships needs an administrator token, checking the projects you can reach instead");
+            audit_reachable_projects(token, username, user_id).await
+        }
+        status if status.is_success() => {
+            let memberships = fetch_all_pages(token, &format!("https://gitlab.com/api/v4/users/{}/memberships", user_id))?;
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec![
+                    Cell::new("Source").add_attribute(Attribute::Bold),
+                    Cell::new("Type").add_attribute(Attribute::Bold),
+                    Cell::new("Access").add_attribute(Attribute::Bold),
+                ]);
+            for membership in &memberships {
+                let source_type = match membership["source_type"].as_str().unwrap_or("") {
+                    "Namespace" => "group",
+                    _ => "project",
+                };
+                table.add_row(vec![
+                    Cell::new(membership["source_name"].as_str().unwrap_or("")),
+                    Cell::new(source_type),
+                    Cell::new(access_level_name(membership["access_level"].as_u64().unwrap_or_default())),
+                ]);
+            }
+            println!("{table}");
+            println!("@{} is a direct member of {} project(s) and group(s)", username, memberships.len());
+            Ok(())
+        }
+        status => Err(Box::from(format!("Failed to fetch memberships of @{}: {}", username, status))),
+    }
+}
+
+async fn audit_reachable_projects(token: &str, username: &str, user_id: u64) -> Result<(), Box<dyn Error>> {
+    let projects = fetch_member_projects(token).await?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Project").add_attribute(Attribute::Bold),
+            Cell::new("Access").add_attribute(Attribute::Bold),
+            Cell::new("Expires At").add_attribute(Attribute::Bold),
+        ]);
+
+    let mut count = 0;
+    let mut failed: Vec<String> = Vec::new();
+    for project in &projects {
+        let project_id = project["id"].as_u64().unwrap_or_default();
+        let path = project["path_with_namespace"].as_str().unwrap_or("");
+        let response = API_CLIENT
+            .get(format!("https://gitlab.com/api/v4/projects/{}/members/all/{}", project_id, user_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()?;
+        // Only a 404 means the user has no access, anything else leaves the audit incomplete
+        if response.status() == StatusCode::NOT_FOUND {
+            continue;
+        }
+        if !response.status().is_success() {
+            failed.push(format!("{} ({})", path, response.status()));
+            continue;
+        }
+        let member: Value = response.json()?;
+        table.add_row(vec![
+            Cell::new(path),
+            Cell::new(access_level_name(member["access_level"].as_u64().unwrap_or_default())),
+            Cell::new(member["expires_at"].as_str().unwrap_or("never")),
+        ]);
+        count += 1;
+    }
+
+    println!("{table}");
+    println!("@{} can access {} of the {} project(s) you can reach", username, count, projects.len());
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        let error_message = format!("Could not check {} project(s): {}", failed.len(), failed.join(", "));
+        Err(Box::from(error_message))
+    }
+}
+
+fn validate_expiry(expires_at: &str) -> Result<String, Box<dyn Error>> {
+    NaiveDate::parse_from_str(expires_at, "%Y-%m-%d")
+        .map(|date| date.to_string())
+        .map_err(|_| Box::from(format!("Invalid expiry date '{}', expected YYYY-MM-DD", expires_at)))
+}
+
+fn access_level_name(level: u64) -> String {
+    match level {
+        0 => String::from("no access"),
+        5 => String::from("minimal access"),
+        10 => String::from("guest"),
+        15 => String::from("planner"),
+        20 => String::from("reporter"),
+        30 => String::from("developer"),
+        40 => String::from("maintainer"),
+        50 => String::from("owner"),
+        _ => level.to_string(),
+    }
+}
@@ -0,0 +1,496 @@
+use std::error::Error;
+use std::path::PathBuf;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, Map, Value};
+
+use crate::client::{fetch_all_pages, API_CLIENT};
+use crate::editor::read_description;
+use crate::projects::fetch_project_id_by_name;
+use crate::users::fetch_user_ids;
+
+pub async fn list_merge_requests(
+    token: &str,
+    project_name: &str,
+    state: Option<String>,
+    author: Option<String>,
+    reviewer: Option<String>,
+    labels: Option<Vec<String>>,
+    target_branch: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut query: Vec<(&str, String)> = vec![("per_page", String::from("100"))];
+    query.push(("state", state.unwrap_or_else(|| String::from("opened"))));
+    if let Some(author) = author {
+        query.push(("author_username", author));
+    }
+    if let Some(reviewer) = reviewer {
+        query.push(("reviewer_username", reviewer));
+    }
+    if let Some(labels) = labels {
+        query.push(("labels", labels.join(",")));
+    }
+    if let Some(target_branch) = target_branch {
+        query.push(("target_branch", target_branch));
+    }
+
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/merge_requests", project_id))
+        .query(&query)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        let merge_requests: Vec<Value> = response.json()?;
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("IID").add_attribute(Attribute::Bold),
+                Cell::new("Title").add_attribute(Attribute::Bold),
+                Cell::new("Author").add_attribute(Attribute::Bold),
+                Cell::new("Branches").add_attribute(Attribute::Bold),
+                Cell::new("State").add_attribute(Attribute::Bold),
+                Cell::new("Labels").add_attribute(Attribute::Bold),
+                Cell::new("Updated At").add_attribute(Attribute::Bold),
+            ]);
+
+        for merge_request in merge_requests {
+            let state = merge_request["state"].as_str().unwrap_or("");
+            let labels: Vec<&str> = merge_request["labels"]
+                .as_array()
+                .map(|labels| labels.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            table.add_row(vec![
+                Cell::new(format!("!{}", merge_request["iid"].as_u64().unwrap_or_default())),
+                Cell::new(merge_request["title"].as_str().unwrap_or("")),
+                Cell::new(merge_request["author"]["username"].as_str().unwrap_or("")),
+                Cell::new(format!(
+                    "{} → {}",
+                    merge_request["source_branch"].as_str().unwrap_or(""),
+                    merge_request["target_branch"].as_str().unwrap_or("")
+                )),
+                Cell::new(state).fg(state_color(state)),
+                Cell::new(labels.join(", ")),
+                Cell::new(merge_request["updated_at"].as_str().unwrap_or("")),
+            ]);
+        }
+        println!("{table}");
+        Ok(())
+    } else {
+        let error_message = format!("Failed to fetch merge requests: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn view_merge_request(token: &str, project_name: &str, iid: u64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let merge_request_url = format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}", project_id, iid);
+
+    let response = API_CLIENT
+        .get(&merge_request_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch merge request !{}: {}", iid, response.status());
+        return Err(Box::from(error_message));
+    }
+    let merge_request: Value = response.json()?;
+
+    let state = merge_request["state"].as_str().unwrap_or("");
+    println!("{} {}  [{}]", format!("!{}", iid).bold(), merge_request["title"].as_str().unwrap_or("").bold(), colorize_state(state));
+    println!(
+        "Author: @{}   Branches: {} → {}",
+        merge_request["author"]["username"].as_str().unwrap_or(""),
+        merge_request["source_branch"].as_str().unwrap_or(""),
+        merge_request["target_branch"].as_str().unwrap_or("")
+    );
+    let pipeline_status = merge_request["head_pipeline"]["status"].as_str().unwrap_or("none");
+    println!("Pipeline: {}", colorize_pipeline_status(pipeline_status));
+
+    let approvals = API_CLIENT
+        .get(format!("{}/approvals", merge_request_url))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if approvals.status().is_success() {
+        let approvals: Value = approvals.json()?;
+        let approved_by: Vec<String> = approvals["approved_by"]
+            .as_array()
+            .map(|users| users.iter()
+                .filter_map(|approval| approval["user"]["username"].as_str())
+                .map(|username| format!("@{}", username))
+                .collect())
+            .unwrap_or_default();
+        let approvals_left = approvals["approvals_left"].as_u64().unwrap_or_default();
+        if approved_by.is_empty() {
+            println!("Approvals: none, {} required", approvals_left);
+        } else {
+            println!("Approvals: {} ({} more required)", approved_by.join(", "), approvals_left);
+        }
+    }
+
+    if let Some(labels) = merge_request["labels"].as_array().filter(|labels| !labels.is_empty()) {
+        let labels: Vec<&str> = labels.iter().filter_map(Value::as_str).collect();
+        println!("Labels: {}", labels.join(", "));
+    }
+    println!("Web URL \"{}\"", merge_request["web_url"].as_str().unwrap_or(""));
+
+    println!("\n{}", "Description".bold());
+    match merge_request["description"].as_str().filter(|text| !text.trim().is_empty()) {
+        Some(description) => println!("{}", description),
+        None => println!("{}", "No description provided.".dimmed()),
+    }
+
+    let discussions = fetch_all_pages(token, &format!("{}/discussions", merge_request_url))?;
+    let discussions: Vec<&Value> = discussions
+        .iter()
+        .filter(|discussion| !discussion["notes"][0]["system"].as_bool().unwrap_or(false))
+        .collect();
+    let unresolved = discussions
+        .iter()
+        .filter(|discussion| discussion["notes"][0]["resolvable"].as_bool().unwrap_or(false)
+            && !discussion["notes"][0]["resolved"].as_bool().unwrap_or(false))
+        .count();
+    println!("\n{}", format!("Discussions ({}, {} unresolved)", discussions.len(), unresolved).bold());
+    for discussion in discussions {
+        let note = &discussion["notes"][0];
+        let first_line = note["body"].as_str().unwrap_or("").lines().next().unwrap_or("");
+        let replies = discussion["notes"].as_array().map(|notes| notes.len().saturating_sub(1)).unwrap_or_default();
+        println!(
+            "  @{}: {} ({} replies)",
+            note["author"]["username"].as_str().unwrap_or(""),
+            first_line,
+            replies
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_merge_request(
+    token: &str,
+    project_name: &str,
+    source_branch: &str,
+    target_branch: Option<String>,
+    title: &str,
+    description: Option<String>,
+    description_file: Option<PathBuf>,
+    use_editor: bool,
+    assignees: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let description = read_description(description, description_file, use_editor)?;
+    let target_branch = match target_branch {
+        Some(target_branch) => target_branch,
+        None => fetch_default_branch(token, project_id)?,
+    };
+    let assignee_ids = fetch_user_ids(token, &assignees).await?;
+
+    let mut body = Map::new();
+    body.insert(String::from("source_branch"), json!(source_branch));
+    body.insert(String::from("target_branch"), json!(target_branch));
+    body.insert(String::from("title"), json!(title));
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    if !assignee_ids.is_empty() {
+        body.insert(String::from("assignee_ids"), json!(assignee_ids));
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/merge_requests", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        let merge_request: Value = response.json()?;
+        println!("Merge request !{} was created successfully", merge_request["iid"].as_u64().unwrap_or_default());
+        println!("Web URL \"{}\"", merge_request["web_url"].as_str().unwrap_or(""));
+        Ok(())
+    } else {
+        let error_message = format!("Failed to create merge request: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn approve_merge_request(token: &str, project_name: &str, iid: u64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}/approve", project_id, iid))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Merge request !{} approved.", iid);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to approve merge request !{}: {}", iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn merge_merge_request(
+    token: &str,
+    project_name: &str,
+    iid: u64,
+    when_pipeline_succeeds: bool,
+    squash: bool,
+    squash_message: Option<String>,
+    remove_source_branch: bool,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut body = Map::new();
+    body.insert(String::from("merge_when_pipeline_succeeds"), json!(when_pipeline_succeeds));
+    body.insert(String::from("squash"), json!(squash));
+    body.insert(String::from("should_remove_source_branch"), json!(remove_source_branch));
+    if let Some(squash_message) = squash_message {
+        body.insert(String::from("squash_commit_message"), json!(squash_message));
+    }
+
+    let response = API_CLIENT
+        .put(format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}/merge", project_id, iid))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        if when_pipeline_succeeds {
+            println!("Merge request !{} will be merged when its pipeline succeeds.", iid);
+        } else {
+            println!("Merge request !{} merged.", iid);
+        }
+        Ok(())
+    } else {
+        let error_message = format!("Failed to merge merge request !{}: {}", iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+// Close or reopen a merge request, `state_event` is either "close" or "reopen"
+pub async fn set_merge_request_state(token: &str, project_name: &str, iid: u64, state_event: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .put(format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}", project_id, iid))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "state_event": state_event }))
+        .send()?;
+
+    if response.status().is_success() {
+        let merge_request: Value = response.json()?;
+        println!("Merge request !{} is now {}.", iid, merge_request["state"].as_str().unwrap_or(state_event));
+        Ok(())
+    } else {
+        let error_message = format!("Failed to {} merge request !{}: {}", state_event, iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn list_merge_request_discussions(token: &str, project_name: &str, iid: u64, unresolved_only: bool) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let discussions = fetch_all_pages(
+        token,
+        &format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}/discussions", project_id, iid),
+    )?;
+
+    for discussion in discussions {
+        let notes = match discussion["notes"].as_array() {
+            Some(notes) if !notes.is_empty() => notes,
+            _ => continue,
+        };
+        let first = &notes[0];
+        if first["system"].as_bool().unwrap_or(false) {
+            continue;
+        }
+
+        let resolvable = first["resolvable"].as_bool().unwrap_or(false);
+        let resolved = notes.iter().all(|note| note["resolved"].as_bool().unwrap_or(false));
+        if unresolved_only && (!resolvable || resolved) {
+            continue;
+        }
+
+        let state = if !resolvable {
+            String::new()
+        } else if resolved {
+            format!(" [{}]", "resolved".green())
+        } else {
+            format!(" [{}]", "unresolved".yellow())
+        };
+        let position = describe_position(&first["position"])
+            .map(|position| format!("  {}", position.cyan()))
+            .unwrap_or_default();
+        println!("{} {}{}{}", "Thread".bold(), discussion["id"].as_str().unwrap_or(""), state, position);
+
+        for (index, note) in notes.iter().enumerate() {
+            let indent = if index == 0 { "  " } else { "    ↳ " };
+            println!(
+                "{}{} {}",
+                indent,
+                format!("@{}", note["author"]["username"].as_str().unwrap_or("")).bold(),
+                note["created_at"].as_str().unwrap_or("").dimmed()
+            );
+            for line in note["body"].as_str().unwrap_or("").lines() {
+                println!("{}  {}", " ".repeat(indent.chars().count()), line);
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+pub async fn comment_on_merge_request(
+    token: &str,
+    project_name: &str,
+    iid: u64,
+    discussion_id: Option<String>,
+    body: Option<String>,
+    body_file: Option<PathBuf>,
+    use_editor: bool,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let body = read_description(body, body_file, use_editor)?
+        .ok_or("A comment body is required, pass --body, --body-file or --editor")?;
+
+    // Replies go to the thread, everything else starts a new note
+    let url = match &discussion_id {
+        Some(discussion_id) => format!(
+            "https://gitlab.com/api/v4/projects/{}/merge_requests/{}/discussions/{}/notes",
+            project_id, iid, discussion_id
+        ),
+        None => format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}/notes", project_id, iid),
+    };
+    let response = API_CLIENT
+        .post(url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "body": body }))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Comment added to merge request !{}.", iid);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to comment on merge request !{}: {}", iid, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn resolve_merge_request_discussion(
+    token: &str,
+    project_name: &str,
+    iid: u64,
+    discussion_id: &str,
+    resolved: bool,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .put(format!(
+            "https://gitlab.com/api/v4/projects/{}/merge_requests/{}/discussions/{}",
+            project_id, iid, discussion_id
+        ))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "resolved": resolved }))
+        .send()?;
+
+    if response.status().is_success() {
+        let state = if resolved { "resolved" } else { "unresolved" };
+        println!("Thread {} marked as {}.", discussion_id, state);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to update thread {}: {}", discussion_id, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn show_merge_request_diff(token: &str, project_name: &str, iid: u64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let diffs = fetch_all_pages(
+        token,
+        &format!("https://gitlab.com/api/v4/projects/{}/merge_requests/{}/diffs", project_id, iid),
+    )?;
+
+    for diff in diffs {
+        let old_path = diff["old_path"].as_str().unwrap_or("");
+        let new_path = diff["new_path"].as_str().unwrap_or("");
+        println!("{}", format!("diff --git a/{} b/{}", old_path, new_path).bold());
+        if diff["new_file"].as_bool().unwrap_or(false) {
+            println!("{}", "new file".bold());
+        } else if diff["deleted_file"].as_bool().unwrap_or(false) {
+            println!("{}", "deleted file".bold());
+        } else if diff["renamed_file"].as_bool().unwrap_or(false) {
+            println!("{}", format!("rename {} → {}", old_path, new_path).bold());
+        }
+        println!("{}", format!("--- a/{}", old_path).bold());
+        println!("{}", format!("+++ b/{}", new_path).bold());
+
+        // Colour each line the same way job traces are coloured
+        for line in diff["diff"].as_str().unwrap_or("").lines() {
+            if line.starts_with("@@") {
+                println!("{}", line.cyan());
+            } else if line.starts_with('+') {
+                println!("{}", line.green());
+            } else if line.starts_with('-') {
+                println!("{}", line.red());
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Render a diff note position as `path:line`
+fn describe_position(position: &Value) -> Option<String> {
+    if position.is_null() {
+        return None;
+    }
+    let path = position["new_path"].as_str().or_else(|| position["old_path"].as_str())?;
+    match position["new_line"].as_u64().or_else(|| position["old_line"].as_u64()) {
+        Some(line) => Some(format!("{}:{}", path, line)),
+        None => Some(path.to_string()),
+    }
+}
+
+fn fetch_default_branch(token: &str, project_id: u64) -> Result<String, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to fetch project details: {}", response.status())));
+    }
+    let project: Value = response.json()?;
+    project["default_branch"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| Box::from("Project has no default branch"))
+}
+
+fn state_color(state: &str) -> Color {
+    match state {
+        "opened" => Color::Green,
+        "merged" => Color::Blue,
+        "closed" => Color::Red,
+        _ => Color::Reset,
+    }
+}
+
+fn colorize_state(state: &str) -> String {
+    match state {
+        "opened" => state.green().to_string(),
+        "merged" => state.blue().to_string(),
+        "closed" => state.red().to_string(),
+        _ => state.to_string(),
+    }
+}
+
+fn colorize_pipeline_status(status: &str) -> String {
+    match status {
+        "success" => format!("✅ {}", status.green()),
+        "failed" => format!("❌ {}", status.red()),
+        "running" | "pending" => status.yellow().to_string(),
+        _ => status.to_string(),
+    }
+}
@@ -0,0 +1,156 @@
+use std::error::Error;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::Value;
+
+use crate::client::fetch_limited_pages;
+use crate::pipelines::{fetch_pipeline, filter_pipelines, parse_coverage, parse_duration, window_start, MAX_PIPELINES};
+use crate::projects::fetch_project_id_by_name;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Show how coverage changed across the pipelines of a branch and flag drops
+// larger than the threshold, in percentage points. Fails when the latest
+// pipeline dropped by more than the threshold so it can gate a CI job.
+pub async fn show_coverage_trend(
+    token: &str,
+    project_name: &str,
+    last: &str,
+    branch: &str,
+    threshold: f64,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let window = parse_duration(last)?;
+    let since = window_start(window)?;
+
+    // Newest first so the cap keeps the latest pipeline, which the gate checks
+    let url = format!("https://gitlab.com/api/v4/projects/{}/pipelines", project_id);
+    let query = [("ref", branch), ("updated_after", since.as_str()), ("order_by", "id"), ("sort", "desc")];
+    let (pipelines, more) = fetch_limited_pages(token, &url, &query, MAX_PIPELINES)?;
+    if more {
+        eprintln!(
+            "{}",
+            format!("Only the latest {} pipelines are examined, pass a shorter --last to see all", MAX_PIPELINES).dimmed()
+        );
+    }
+    let mut pipelines = filter_pipelines(pipelines, window);
+    pipelines.reverse();
+
+    // Coverage is only returned by the single pipeline endpoint
+    let mut points: Vec<(Value, f64)> = Vec::new();
+    for pipeline in &pipelines {
+        let pipeline_id = pipeline["id"].as_i64().unwrap_or_default();
+        match fetch_pipeline(token, project_id, pipeline_id) {
+            Ok(details) => {
+                if let Some(coverage) = parse_coverage(&details["coverage"]) {
+                    points.push((details, coverage));
+                }
+            }
+            Err(e) => eprintln!("Skipping pipeline {}: {}", pipeline_id, e),
+        }
+    }
+
+    if points.is_empty() {
+        println!("No pipelines with coverage on \"{}\" branch in the last {}.", branch, last);
+        return Ok(());
+    }
+
+    let values: Vec<f64> = points.iter().map(|(_, coverage)| *coverage).collect();
+    println!(
+        "{} for {} on \"{}\" branch in the last {}",
+        "Coverage".bold(),
+        project_name,
+        branch,
+        last
+    );
+    println!(
+        "{}  {:.2}% → {:.2}%",
+        sparkline(&values),
+        values[0],
+        values[values.len() - 1]
+    );
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Pipeline ID").add_attribute(Attribute::Bold),
+            Cell::new("Created At").add_attribute(Attribute::Bold),
+            Cell::new("SHA").add_attribute(Attribute::Bold),
+            Cell::new("Coverage").add_attribute(Attribute::Bold),
+            Cell::new("Change").add_attribute(Attribute::Bold),
+        ]);
+
+    let mut drops = 0;
+    for (index, (pipeline, coverage)) in points.iter().enumerate() {
+        let change_cell = match index.checked_sub(1).map(|previous| coverage - points[previous].1) {
+            Some(change) if change < -threshold => {
+                drops += 1;
+                Cell::new(format!("{:+.2}", change)).fg(Color::Red).add_attribute(Attribute::Bold)
+            }
+            Some(change) if change < 0.0 => Cell::new(format!("{:+.2}", change)).fg(Color::Yellow),
+            Some(change) if change > 0.0 => Cell::new(format!("{:+.2}", change)).fg(Color::Green),
+            Some(change) => Cell::new(format!("{:+.2}", change)),
+            None => Cell::new("-"),
+        };
+        table.add_row(vec![
+            Cell::new(pipeline["id"].to_string()),
+            Cell::new(pipeline["created_at"].as_str().unwrap_or("")),
+            Cell::new(pipeline["sha"].as_str().unwrap_or("").chars().take(8).collect::<String>()),
+            Cell::new(format!("{:.2}%", coverage)),
+            change_cell,
+        ]);
+    }
+    println!("{table}");
+
+    if drops > 0 {
+        println!("{}", format!("{} drop(s) larger than {:.2} points.", drops, threshold).yellow());
+    }
+
+    if let [.., (_, previous), (pipeline, latest)] = points.as_slice() {
+        if previous - latest > threshold {
+            let error_message = format!(
+                "Coverage dropped from {:.2}% to {:.2}% in pipeline {}, more than the {:.2} point threshold",
+                previous, latest, pipeline["id"], threshold
+            );
+            return Err(Box::from(error_message));
+        }
+    }
+    Ok(())
+}
+
+// Scale the values between their minimum and maximum
+fn sparkline(values: &[f64]) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if max <= min {
+                SPARKS[SPARKS.len() / 2]
+            } else {
+                let level = ((value - min) / (max - min) * (SPARKS.len() - 1) as f64).round() as usize;
+                SPARKS[level.min(SPARKS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[10.0, 20.0, 15.0]), "▁█▅");
+        assert_eq!(sparkline(&[0.0, 100.0 / 7.0, 100.0]), "▁▂█");
+    }
+
+    #[test]
+    fn sparkline_of_flat_or_empty_values() {
+        assert_eq!(sparkline(&[80.0, 80.0]), "▅▅");
+        assert_eq!(sparkline(&[42.0]), "▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
@@ -0,0 +1,265 @@
+use std::error::Error;
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::client::API_CLIENT;
+use crate::pipelines::{colorize_status, fetch_job_trace, fetch_pipeline, fetch_pipeline_bridges, fetch_pipeline_jobs, print_pipeline};
+use crate::projects::fetch_project_id_by_name;
+
+// Lines that usually mark where a job started going wrong
+const ERROR_MARKERS: [&str; 8] = [
+    "error",
+    "fatal",
+    "failed",
+    "exception",
+    "panicked",
+    "traceback",
+    "killed",
+    "no such file",
+];
+
+// Summarise why a pipeline failed: each failed job, its failure reason, a
+// diagnosis and the part of its log around the first error
+pub async fn explain_pipeline_failure(
+    token: &str,
+    project_name: &str,
+    pipeline_id: Option<i64>,
+    branch: Option<String>,
+    lines: usize,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let pipeline = match (pipeline_id, branch) {
+        (Some(pipeline_id), _) => fetch_pipeline(token, project_id, pipeline_id)?,
+        (None, Some(branch)) => fetch_latest_pipeline(token, project_id, &branch)?,
+        (None, None) => return Err(Box::from("Specify --pipeline or --latest with a branch")),
+    };
+    let pipeline_id = pipeline["id"].as_i64().unwrap_or_default();
+    print_pipeline(&pipeline);
+
+    let failed_jobs: Vec<Value> = fetch_pipeline_jobs(token, project_id, pipeline_id, false)?
+        .into_iter()
+        .filter(|job| job["status"].as_str() == Some("failed"))
+        .collect();
+    let failed_bridges: Vec<Value> = fetch_pipeline_bridges(token, project_id, pipeline_id)?
+        .into_iter()
+        .filter(|bridge| bridge["status"].as_str() == Some("failed"))
+        .collect();
+
+    if failed_jobs.is_empty() && failed_bridges.is_empty() {
+        println!("\nNo failed jobs in pipeline {}.", pipeline_id);
+        return Ok(());
+    }
+
+    for job in &failed_jobs {
+        let job_id = job["id"].as_i64().unwrap_or_default();
+        let failure_reason = job["failure_reason"].as_str().unwrap_or("unknown_failure");
+        println!(
+            "\n{} {} ({}): {}",
+            format!("Job ID {}", job_id).bold(),
+            job["name"].as_str().unwrap_or(""),
+            job["stage"].as_str().unwrap_or(""),
+            failure_reason.red()
+        );
+        if job["allow_failure"].as_bool().unwrap_or(false) {
+            println!("{}", "This job is allowed to fail and did not block the pipeline.".dimmed());
+        }
+        println!("Web URL \"{}\"", job["web_url"].as_str().unwrap_or(""));
+
+        let trace = match fetch_job_trace(token, project_id, job_id) {
+            Ok(trace) => strip_trace(&trace),
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        if let Some(diagnosis) = diagnose(failure_reason, &trace) {
+            println!("Diagnosis: {}", diagnosis.yellow());
+        }
+        print_excerpt(&trace, lines);
+    }
+
+    // Trigger jobs fail when their downstream pipeline does, point at it instead
+    for bridge in &failed_bridges {
+        let downstream = &bridge["downstream_pipeline"];
+        println!(
+            "\n{} {}: downstream pipeline {} {}",
+            format!("Trigger job ID {}", bridge["id"]).bold(),
+            bridge["name"].as_str().unwrap_or(""),
+            downstream["id"],
+            colorize_status(downstream["status"].as_str().unwrap_or(""))
+        );
+        println!("Web URL \"{}\"", downstream["web_url"].as_str().unwrap_or(""));
+    }
+    Ok(())
+}
+
+fn fetch_latest_pipeline(token: &str, project_id: u64, branch: &str) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines/latest", project_id))
+        .query(&[("ref", branch)])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
+    } else {
+        let error_message = format!("Failed to fetch the latest pipeline on {}: {}", branch, response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+// Recognise the failures that have a known cause before showing the log
+fn diagnose(failure_reason: &str, trace: &str) -> Option<String> {
+    match failure_reason {
+        "runner_system_failure" => return Some(String::from("runner system failure, the job never ran its script; retrying usually helps")),
+        "job_execution_timeout" | "stuck_or_timeout_failure" => return Some(String::from("the job timed out")),
+        "runner_unsupported" | "no_matching_runner" => return Some(String::from("no runner could pick up the job, check its tags")),
+        _ => {}
+    }
+
+    let lower = trace.to_lowercase();
+    if lower.contains("oomkilled") || lower.contains("out of memory") || lower.contains("exit code 137") || lower.contains("signal: killed") {
+        return Some(String::from("the job ran out of memory (OOM killed)"));
+    }
+    if lower.contains("execution took longer than") {
+        return Some(String::from("the job timed out"));
+    }
+    if lower.contains("error: job failed (system failure)") {
+        return Some(String::from("runner system failure"));
+    }
+    trace
+        .lines()
+        .rev()
+        .find_map(|line| line.split("ERROR: Job failed: exit code ").nth(1))
+        .map(|code| format!("the script exited with code {}", code.trim()))
+}
+
+fn print_excerpt(trace: &str, lines: usize) {
+    let trace_lines: Vec<&str> = trace.lines().collect();
+    let (first_error, start, end) = excerpt_range(&trace_lines, lines);
+    if start > 0 {
+        println!("{}", format!("... {} earlier lines", start).dimmed());
+    }
+    for (index, line) in trace_lines[start..end].iter().enumerate() {
+        if Some(start + index) == first_error {
+            println!("{}", line.red().bold());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+// The first error line and the lines around it, or the tail of the trace when
+// nothing looks like an error
+fn excerpt_range(trace_lines: &[&str], lines: usize) -> (Option<usize>, usize, usize) {
+    // Skip the runner preamble and the runner's own closing error line
+    let first_error = trace_lines.iter().position(|line| {
+        let lower = line.to_lowercase();
+        !lower.starts_with("error: job failed") && ERROR_MARKERS.iter().any(|marker| lower.contains(marker))
+    });
+
+    let (start, end) = match first_error {
+        Some(index) => {
+            let start = index.saturating_sub(lines / 4);
+            (start, (start + lines).min(trace_lines.len()))
+        }
+        None => (trace_lines.len().saturating_sub(lines), trace_lines.len()),
+    };
+    (first_error, start, end)
+}
+
+// Drop ANSI colours and the runner's collapsible section markers
+fn strip_trace(trace: &str) -> String {
+    let mut cleaned = String::with_capacity(trace.len());
+    let mut chars = trace.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        cleaned.push(c);
+    }
+
+    cleaned
+        .lines()
+        .filter_map(|line| {
+            // A carriage return overwrites the line, keep what ends up visible
+            let line = line.rsplit('\r').find(|part| !part.is_empty()).unwrap_or("");
+            if line.starts_with("section_start:") || line.starts_with("section_end:") {
+                None
+            } else {
+                Some(line)
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_trace_drops_colours_and_section_markers() {
+        let trace = "\x1b[0Ksection_start:1700000000:step_script\r\x1b[0K\x1b[32;1m$ cargo test\x1b[0;m\n\
+                     running 1 test\n\
+                     progress 10%\rprogress 100%\n\
+                     \x1b[0Ksection_end:1700000001:step_script\r\x1b[0K\n\
+                     \x1b[31;1mERROR: Job failed: exit code 101\x1b[0;m";
+        assert_eq!(
+            strip_trace(trace),
+            "$ cargo test\nrunning 1 test\nprogress 100%\nERROR: Job failed: exit code 101"
+        );
+    }
+
+    #[test]
+    fn diagnose_prefers_the_failure_reason() {
+        assert_eq!(
+            diagnose("runner_system_failure", "ERROR: Job failed: exit code 1").as_deref(),
+            Some("runner system failure, the job never ran its script; retrying usually helps")
+        );
+        assert_eq!(diagnose("job_execution_timeout", "").as_deref(), Some("the job timed out"));
+    }
+
+    #[test]
+    fn diagnose_reads_the_trace() {
+        assert_eq!(
+            diagnose("script_failure", "Killed\nERROR: Job failed: exit code 137").as_deref(),
+            Some("the job ran out of memory (OOM killed)")
+        );
+        assert_eq!(
+            diagnose("script_failure", "ERROR: Job failed: execution took longer than 1h0m0s seconds").as_deref(),
+            Some("the job timed out")
+        );
+        assert_eq!(
+            diagnose("script_failure", "$ make\nERROR: Job failed: exit code 2\n").as_deref(),
+            Some("the script exited with code 2")
+        );
+        assert_eq!(diagnose("script_failure", "$ make\nok"), None);
+    }
+
+    #[test]
+    fn excerpt_starts_before_the_first_error() {
+        let trace: Vec<String> = (0..20).map(|index| format!("line {}", index)).collect();
+        let mut trace_lines: Vec<&str> = trace.iter().map(String::as_str).collect();
+        trace_lines[10] = "error[E0308]: mismatched types";
+        trace_lines[19] = "ERROR: Job failed: exit code 101";
+        assert_eq!(excerpt_range(&trace_lines, 8), (Some(10), 8, 16));
+        // Never reaches past the end of the trace
+        assert_eq!(excerpt_range(&trace_lines, 40), (Some(10), 0, 20));
+    }
+
+    #[test]
+    fn excerpt_falls_back_to_the_tail() {
+        let trace_lines = ["one", "two", "three", "ERROR: Job failed: exit code 1"];
+        assert_eq!(excerpt_range(&trace_lines, 2), (None, 2, 4));
+        assert_eq!(excerpt_range(&trace_lines, 10), (None, 0, 4));
+        assert_eq!(excerpt_range(&[], 10), (None, 0, 0));
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+use std::error::Error;
+use colored::{ColoredString, Colorize};
+use serde_json::{json, Value};
+
+use crate::client::API_CLIENT;
+use crate::commands::GraphFormat;
+use crate::pipelines::{colorize_status, fetch_pipeline, fetch_pipeline_bridges, fetch_pipeline_jobs};
+use crate::projects::fetch_project_id_by_name;
+
+// The REST API has neither `needs` nor the stage order, so both come from GraphQL
+const NEEDS_QUERY: &str = "query($path: ID!, $iid: ID!, $after: String) {
+  project(fullPath: $path) {
+    pipeline(iid: $iid) {
+      stages { nodes { name } }
+      jobs(after: $after) {
+        nodes { name schedulingType needs { nodes { name } } }
+        pageInfo { hasNextPage endCursor }
+      }
+    }
+  }
+}";
+
+// Job name to its `needs`, None when the job is scheduled by stage
+type JobNeeds = HashMap<String, Option<Vec<String>>>;
+
+struct PipelineStructure {
+    // Stage names in the order they run
+    stages: Vec<String>,
+    needs: JobNeeds,
+}
+
+struct GraphNode {
+    id: String,
+    name: String,
+    stage: String,
+    status: String,
+    // Jobs without `needs` wait for the whole previous stage
+    needs: Option<Vec<String>>,
+    downstream: Option<Value>,
+}
+
+pub async fn show_pipeline_graph(token: &str, project_name: &str, pipeline_id: i64, format: GraphFormat) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let pipeline = fetch_pipeline(token, project_id, pipeline_id)?;
+
+    let mut jobs = fetch_pipeline_jobs(token, project_id, pipeline_id, false)?;
+    jobs.extend(fetch_pipeline_bridges(token, project_id, pipeline_id)?);
+    jobs.sort_by_key(|job| job["id"].as_i64().unwrap_or_default());
+
+    let PipelineStructure { stages: pipeline_stages, needs } = match fetch_pipeline_structure(token, project_id, &pipeline) {
+        Ok(structure) => structure,
+        Err(e) => {
+            eprintln!("Could not fetch job dependencies, falling back to stage order: {}", e);
+            PipelineStructure { stages: Vec::new(), needs: HashMap::new() }
+        }
+    };
+
+    let nodes: Vec<GraphNode> = jobs
+        .iter()
+        .map(|job| {
+            let name = job["name"].as_str().unwrap_or("").to_string();
+            GraphNode {
+                id: format!("job_{}", job["id"].as_i64().unwrap_or_default()),
+                needs: needs.get(&name).cloned().flatten(),
+                stage: job["stage"].as_str().unwrap_or("").to_string(),
+                status: job["status"].as_str().unwrap_or("").to_string(),
+                downstream: Some(job["downstream_pipeline"].clone()).filter(|downstream| !downstream.is_null()),
+                name,
+            }
+        })
+        .collect();
+
+    let stages = stage_order(&pipeline_stages, &nodes);
+    let edges = dependency_edges(&nodes, &stages);
+
+    match format {
+        GraphFormat::Ascii => print_ascii(&pipeline, &nodes, &stages),
+        GraphFormat::Dot => print_dot(pipeline_id, &nodes, &stages, &edges),
+        GraphFormat::Mermaid => print_mermaid(&nodes, &stages, &edges),
+    }
+    Ok(())
+}
+
+fn fetch_pipeline_structure(token: &str, project_id: u64, pipeline: &Value) -> Result<PipelineStructure, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to fetch project details: {}", response.status())));
+    }
+    let project: Value = response.json()?;
+
+    // The jobs connection returns at most 100 jobs per page
+    let mut stages: Vec<String> = Vec::new();
+    let mut jobs: Vec<Value> = Vec::new();
+    let mut after = Value::Null;
+    loop {
+        let response = API_CLIENT
+            .post("https://gitlab.com/api/graphql")
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({
+                "query": NEEDS_QUERY,
+                "variables": {
+                    "path": project["path_with_namespace"],
+                    "iid": pipeline["iid"].to_string(),
+                    "after": after,
+                },
+            }))
+            .read_only()
+            .send()?;
+        if !response.status().is_success() {
+            return Err(Box::from(format!("GraphQL request failed: {}", response.status())));
+        }
+        let body: Value = response.json()?;
+        let pipeline = &body["data"]["project"]["pipeline"];
+        if after.is_null() {
+            stages = pipeline["stages"]["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|stage| stage["name"].as_str())
+                .map(String::from)
+                .collect();
+        }
+        let connection = &pipeline["jobs"];
+        let nodes = connection["nodes"].as_array().ok_or("GraphQL response has no jobs")?;
+        jobs.extend(nodes.iter().cloned());
+
+        let page_info = &connection["pageInfo"];
+        match page_info["endCursor"].as_str() {
+            Some(cursor) if page_info["hasNextPage"].as_bool() == Some(true) => after = json!(cursor),
+            _ => break,
+        }
+    }
+
+    let needs = jobs
+        .iter()
+        .map(|job| {
+            let needs = if job["schedulingType"].as_str() == Some("dag") {
+                Some(
+                    job["needs"]["nodes"]
+                        .as_array()
+                        .map(|needs| needs.iter().filter_map(|need| need["name"].as_str()).map(String::from).collect())
+                        .unwrap_or_default(),
+                )
+            } else {
+                None
+            };
+            (job["name"].as_str().unwrap_or("").to_string(), needs)
+        })
+        .collect();
+    Ok(PipelineStructure { stages, needs })
+}
+
+// Stages in the pipeline's own order. Without it, fall back to the order jobs
+// appear in, which a retried job can upset since it gets a newer ID.
+fn stage_order(pipeline_stages: &[String], nodes: &[GraphNode]) -> Vec<String> {
+    let mut stages: Vec<String> = pipeline_stages
+        .iter()
+        .filter(|stage| nodes.iter().any(|node| &node.stage == *stage))
+        .cloned()
+        .collect();
+    for node in nodes {
+        if !stages.contains(&node.stage) {
+            stages.push(node.stage.clone());
+        }
+    }
+    stages
+}
+
+// Edges as (from, to) node indexes
+fn dependency_edges(nodes: &[GraphNode], stages: &[String]) -> Vec<(usize, usize)> {
+    let mut edges = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        match &node.needs {
+            Some(needs) => {
+                for need in needs {
+                    if let Some(from) = nodes.iter().position(|other| &other.name == need) {
+                        edges.push((from, index));
+                    }
+                }
+            }
+            None => {
+                let stage = stages.iter().position(|stage| stage == &node.stage).unwrap_or_default();
+                if stage == 0 {
+                    continue;
+                }
+                for (from, other) in nodes.iter().enumerate() {
+                    if other.stage == stages[stage - 1] {
+                        edges.push((from, index));
+                    }
+                }
+            }
+        }
+    }
+    edges
+}
+
+fn print_ascii(pipeline: &Value, nodes: &[GraphNode], stages: &[String]) {
+    println!(
+        "Pipeline ID {}: {} on \"{}\" branch",
+        pipeline["id"],
+        colorize_status(pipeline["status"].as_str().unwrap_or("")),
+        pipeline["ref"].as_str().unwrap_or("")
+    );
+    let width = nodes.iter().map(|node| node.name.chars().count()).max().unwrap_or_default();
+
+    for stage in stages {
+        println!("\n{}", stage.bold());
+        let stage_nodes: Vec<&GraphNode> = nodes.iter().filter(|node| &node.stage == stage).collect();
+        for (position, node) in stage_nodes.iter().enumerate() {
+            let branch = if position + 1 == stage_nodes.len() { "└─" } else { "├─" };
+            let marker = if node.downstream.is_some() { "◆" } else { "●" };
+            let mut line = format!(
+                "{} {} {:<width$}  {}",
+                branch,
+                status_colored(&node.status, marker),
+                node.name,
+                status_colored(&node.status, &node.status),
+                width = width
+            );
+            if let Some(needs) = &node.needs {
+                if !needs.is_empty() {
+                    line.push_str(&format!("  ← {}", needs.join(", ")).dimmed().to_string());
+                }
+            }
+            if let Some(downstream) = &node.downstream {
+                let status = downstream["status"].as_str().unwrap_or("");
+                line.push_str(&format!("  → pipeline {} {}", downstream["id"], status_colored(status, status)));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+fn print_dot(pipeline_id: i64, nodes: &[GraphNode], stages: &[String], edges: &[(usize, usize)]) {
+    println!("digraph pipeline_{} {{", pipeline_id);
+    println!("  rankdir=LR;");
+    println!("  node [shape=box, style=rounded];");
+    for (index, stage) in stages.iter().enumerate() {
+        println!("  subgraph cluster_{} {{", index);
+        println!("    label=\"{}\";", escape_dot_label(stage));
+        for node in nodes.iter().filter(|node| &node.stage == stage) {
+            println!(
+                "    {} [label=\"{}\\n{}\", color={}];",
+                node.id,
+                escape_dot_label(&node.name),
+                node.status,
+                dot_color(&node.status)
+            );
+        }
+        println!("  }}");
+    }
+    for node in nodes {
+        if let Some(downstream) = &node.downstream {
+            let status = downstream["status"].as_str().unwrap_or("");
+            println!(
+                "  pipeline_{} [label=\"pipeline {}\\n{}\", shape=component, color={}];",
+                downstream["id"], downstream["id"], status, dot_color(status)
+            );
+            println!("  {} -> pipeline_{} [style=dashed];", node.id, downstream["id"]);
+        }
+    }
+    for (from, to) in edges {
+        println!("  {} -> {};", nodes[*from].id, nodes[*to].id);
+    }
+    println!("}}");
+}
+
+fn print_mermaid(nodes: &[GraphNode], stages: &[String], edges: &[(usize, usize)]) {
+    println!("flowchart LR");
+    for (index, stage) in stages.iter().enumerate() {
+        println!("  subgraph stage_{} [\"{}\"]", index, escape_mermaid_label(stage));
+        for node in nodes.iter().filter(|node| &node.stage == stage) {
+            println!("    {}[\"{}<br/>{}\"]:::{}", node.id, escape_mermaid_label(&node.name), node.status, mermaid_class(&node.status));
+        }
+        println!("  end");
+    }
+    for node in nodes {
+        if let Some(downstream) = &node.downstream {
+            let status = downstream["status"].as_str().unwrap_or("");
+            println!(
+                "  pipeline_{}[[\"pipeline {}<br/>{}\"]]:::{}",
+                downstream["id"], downstream["id"], status, mermaid_class(status)
+            );
+            println!("  {} -.-> pipeline_{}", node.id, downstream["id"]);
+        }
+    }
+    for (from, to) in edges {
+        println!("  {} --> {}", nodes[*from].id, nodes[*to].id);
+    }
+    println!("  classDef success fill:#d4edda,stroke:#28a745;");
+    println!("  classDef failed fill:#f8d7da,stroke:#dc3545;");
+    println!("  classDef running fill:#cce5ff,stroke:#007bff;");
+    println!("  classDef other fill:#e2e3e5,stroke:#6c757d;");
+}
+
+fn status_colored(status: &str, text: &str) -> ColoredString {
+    match status {
+        "success" => text.green(),
+        "failed" => text.red(),
+        "running" => text.blue(),
+        "pending" | "created" | "waiting_for_resource" | "preparing" => text.yellow(),
+        _ => text.dimmed(),
+    }
+}
+
+fn dot_color(status: &str) -> &'static str {
+    match status {
+        "success" => "green",
+        "failed" => "red",
+        "running" => "blue",
+        _ => "gray",
+    }
+}
+
+fn mermaid_class(status: &str) -> &'static str {
+    match status {
+        "success" => "success",
+        "failed" => "failed",
+        "running" => "running",
+        _ => "other",
+    }
+}
+
+// Backslashes start escape sequences such as `\n` in DOT labels
+fn escape_dot_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, stage: &str, needs: Option<&[&str]>) -> GraphNode {
+        GraphNode {
+            id: format!("job_{}", name),
+            name: name.to_string(),
+            stage: stage.to_string(),
+            status: String::from("success"),
+            needs: needs.map(|needs| needs.iter().map(|need| need.to_string()).collect()),
+            downstream: None,
+        }
+    }
+
+    fn stages(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn stage_jobs_wait_for_the_whole_previous_stage() {
+        let nodes = [
+            node("build", "build", None),
+            node("lint", "build", None),
+            node("unit", "test", None),
+            node("deploy", "deploy", None),
+        ];
+        let edges = dependency_edges(&nodes, &stages(&["build", "test", "deploy"]));
+        assert_eq!(edges, vec![(0, 2), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn needs_replace_stage_edges() {
+        let nodes = [
+            node("build", "build", None),
+            node("lint", "build", None),
+            node("unit", "test", Some(&["build", "missing"])),
+            node("early", "test", Some(&[])),
+        ];
+        let edges = dependency_edges(&nodes, &stages(&["build", "test"]));
+        assert_eq!(edges, vec![(0, 2)]);
+    }
+
+    #[test]
+    fn stage_order_follows_the_pipeline_not_job_ids() {
+        // `build` was retried, so its job sorts after the test jobs
+        let nodes = [node("unit", "test", None), node("build", "build", None), node("extra", "extra", None)];
+        let order = stage_order(&stages(&["build", "test", "deploy"]), &nodes);
+        assert_eq!(order, stages(&["build", "test", "extra"]));
+        assert_eq!(stage_order(&[], &nodes), stages(&["test", "build", "extra"]));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape_dot_label(r#"say "hi" \n"#), r#"say \"hi\" \\n"#);
+        assert_eq!(escape_mermaid_label(r#"say "hi""#), "say #quot;hi#quot;");
+    }
+}
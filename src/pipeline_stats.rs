@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, to_string_pretty, Value};
+
+use crate::client::fetch_limited_pages;
+use crate::commands::OutputFormat;
+use crate::pipelines::{fetch_pipeline, fetch_pipeline_jobs, filter_pipelines, parse_duration, window_start, MAX_PIPELINES};
+use crate::projects::fetch_project_id_by_name;
+
+// How many of the slowest jobs to report
+const SLOWEST_JOBS: usize = 5;
+#[derive(Default)]
+struct JobStats {
+    runs: usize,
+    failures: usize,
+    durations: Vec<f64>,
+    queued: Vec<f64>,
+}
+
+// Report success rate, duration and queue time percentiles, the slowest jobs
+// and jobs that failed and then passed on the same commit
+pub async fn show_pipeline_stats(
+    token: &str,
+    project_name: &str,
+    last: &str,
+    branch: Option<String>,
+    output: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let window = parse_duration(last)?;
+    let since = window_start(window)?;
+
+    // The API can only filter on update time, creation time is checked afterwards
+    let url = format!("https://gitlab.com/api/v4/projects/{}/pipelines", project_id);
+    let mut query = vec![("updated_after", since.as_str()), ("order_by", "id"), ("sort", "desc")];
+    if let Some(branch) = &branch {
+        query.push(("ref", branch.as_str()));
+    }
+    let (pipelines, more) = fetch_limited_pages(token, &url, &query, MAX_PIPELINES)?;
+    let pipelines = filter_pipelines(pipelines, window);
+    if more {
+        eprintln!(
+            "{}",
+            format!("Only the latest {} pipelines are examined, pass a shorter --last to see all", MAX_PIPELINES).dimmed()
+        );
+    }
+
+    let mut statuses: BTreeMap<String, usize> = BTreeMap::new();
+    let mut durations = Vec::new();
+    let mut queued = Vec::new();
+    let mut jobs: BTreeMap<String, JobStats> = BTreeMap::new();
+    // (sha, job name) to the outcome of each attempt, in the order they ran
+    let mut attempts: HashMap<(String, String), Vec<(i64, bool)>> = HashMap::new();
+
+    let mut skipped = 0;
+
+    for pipeline in &pipelines {
+        let pipeline_id = pipeline["id"].as_i64().unwrap_or_default();
+
+        // Durations are only returned by the single pipeline endpoint
+        let details = fetch_pipeline(token, project_id, pipeline_id);
+        let pipeline_jobs = fetch_pipeline_jobs(token, project_id, pipeline_id, true);
+        let (details, pipeline_jobs) = match (details, pipeline_jobs) {
+            (Ok(details), Ok(pipeline_jobs)) => (details, pipeline_jobs),
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("Skipping pipeline {}: {}", pipeline_id, e);
+                skipped += 1;
+                continue;
+            }
+        };
+        *statuses.entry(pipeline["status"].as_str().unwrap_or("").to_string()).or_default() += 1;
+        if let Some(duration) = details["duration"].as_f64() {
+            durations.push(duration);
+        }
+        if let Some(queued_duration) = details["queued_duration"].as_f64() {
+            queued.push(queued_duration);
+        }
+
+        let sha = pipeline["sha"].as_str().unwrap_or("").to_string();
+        for job in pipeline_jobs {
+            let name = job["name"].as_str().unwrap_or("").to_string();
+            let status = job["status"].as_str().unwrap_or("");
+            if status != "success" && status != "failed" {
+                continue;
+            }
+            let stats = jobs.entry(name.clone()).or_default();
+            stats.runs += 1;
+            if status == "failed" {
+                stats.failures += 1;
+            }
+            if let Some(duration) = job["duration"].as_f64() {
+                stats.durations.push(duration);
+            }
+            if let Some(queued_duration) = job["queued_duration"].as_f64() {
+                stats.queued.push(queued_duration);
+            }
+            attempts
+                .entry((sha.clone(), name))
+                .or_default()
+                .push((job["id"].as_i64().unwrap_or_default(), status == "success"));
+        }
+    }
+
+    let flaky = flaky_jobs(attempts);
+
+    let finished: usize = ["success", "failed", "canceled"]
+        .iter()
+        .map(|status| statuses.get(*status).copied().unwrap_or_default())
+        .sum();
+    let success_rate = if finished > 0 {
+        Some(statuses.get("success").copied().unwrap_or_default() as f64 * 100.0 / finished as f64)
+    } else {
+        None
+    };
+
+    let mut slowest: Vec<(&String, f64)> = jobs
+        .iter()
+        .filter_map(|(name, stats)| Some((name, percentile(&stats.durations, 50.0)?)))
+        .collect();
+    slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+    slowest.truncate(SLOWEST_JOBS);
+
+    let examined = pipelines.len() - skipped;
+    if output == OutputFormat::Json {
+        let report = json!({
+            "project": project_name,
+            "branch": branch,
+            "window": last,
+            "pipelines": {
+                "total": examined,
+                "skipped": skipped,
+                "statuses": statuses,
+                "success_rate": success_rate,
+                "duration": { "median": percentile(&durations, 50.0), "p95": percentile(&durations, 95.0) },
+                "queued": { "median": percentile(&queued, 50.0), "p95": percentile(&queued, 95.0) },
+            },
+            "jobs": jobs.iter().map(|(name, stats)| json!({
+                "name": name,
+                "runs": stats.runs,
+                "failures": stats.failures,
+                "duration": { "median": percentile(&stats.durations, 50.0), "p95": percentile(&stats.durations, 95.0) },
+                "queued": { "median": percentile(&stats.queued, 50.0), "p95": percentile(&stats.queued, 95.0) },
+            })).collect::<Vec<Value>>(),
+            "slowest_jobs": slowest.iter().map(|(name, median)| json!({ "name": name, "median": median })).collect::<Vec<Value>>(),
+            "flaky_jobs": flaky.iter().map(|(name, shas)| json!({ "name": name, "occurrences": shas.len(), "shas": shas })).collect::<Vec<Value>>(),
+        });
+        println!("{}", to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} for {} in the last {}{}",
+        "Pipeline stats".bold(),
+        project_name,
+        last,
+        branch.as_ref().map(|branch| format!(" on \"{}\" branch", branch)).unwrap_or_default()
+    );
+    println!(
+        "Pipelines: {} ({})",
+        examined,
+        statuses.iter().map(|(status, count)| format!("{} {}", count, status)).collect::<Vec<String>>().join(", ")
+    );
+    if skipped > 0 {
+        println!("{}", format!("{} pipeline(s) could not be fetched and are left out of these stats.", skipped).yellow());
+    }
+    match success_rate {
+        Some(rate) => {
+            let rate_text = format!("{:.1}%", rate);
+            let rate_text = if rate >= 90.0 { rate_text.green() } else if rate >= 70.0 { rate_text.yellow() } else { rate_text.red() };
+            println!("Success rate: {}", rate_text);
+        }
+        None => println!("Success rate: -"),
+    }
+    println!(
+        "Duration: median {}, p95 {}",
+        format_seconds(percentile(&durations, 50.0)),
+        format_seconds(percentile(&durations, 95.0))
+    );
+    println!(
+        "Queued: median {}, p95 {}",
+        format_seconds(percentile(&queued, 50.0)),
+        format_seconds(percentile(&queued, 95.0))
+    );
+
+    println!("\n{}", "Jobs".bold());
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Runs").add_attribute(Attribute::Bold),
+            Cell::new("Failures").add_attribute(Attribute::Bold),
+            Cell::new("Median").add_attribute(Attribute::Bold),
+            Cell::new("P95").add_attribute(Attribute::Bold),
+            Cell::new("Median Queued").add_attribute(Attribute::Bold),
+            Cell::new("Flaky SHAs").add_attribute(Attribute::Bold),
+        ]);
+    for (name, stats) in &jobs {
+        let failures_cell = if stats.failures > 0 {
+            Cell::new(stats.failures.to_string()).fg(Color::Red)
+        } else {
+            Cell::new("0")
+        };
+        let flaky_count = flaky.get(name).map(Vec::len).unwrap_or_default();
+        let flaky_cell = if flaky_count > 0 {
+            Cell::new(flaky_count.to_string()).fg(Color::Yellow)
+        } else {
+            Cell::new("0")
+        };
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(stats.runs.to_string()),
+            failures_cell,
+            Cell::new(format_seconds(percentile(&stats.durations, 50.0))),
+            Cell::new(format_seconds(percentile(&stats.durations, 95.0))),
+            Cell::new(format_seconds(percentile(&stats.queued, 50.0))),
+            flaky_cell,
+        ]);
+    }
+    println!("{table}");
+
+    if !slowest.is_empty() {
+        println!("\n{}", "Slowest jobs (median)".bold());
+        for (name, median) in &slowest {
+            println!("  {} {}", format_seconds(Some(*median)), name);
+        }
+    }
+    if !flaky.is_empty() {
+        println!("\n{}", "Flaky jobs (failed, then passed on the same commit)".bold());
+        for (name, shas) in &flaky {
+            let short: Vec<String> = shas.iter().map(|sha| sha.chars().take(8).collect()).collect();
+            println!("  {} on {}", name.yellow(), short.join(", "));
+        }
+    }
+    Ok(())
+}
+
+// A job is flaky on a commit when a failed attempt was followed by a passing one.
+// Returns the job names with the SHAs they were flaky on, in a stable order.
+fn flaky_jobs(attempts: HashMap<(String, String), Vec<(i64, bool)>>) -> BTreeMap<String, Vec<String>> {
+    let mut flaky: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for ((sha, name), mut runs) in attempts {
+        runs.sort_by_key(|(id, _)| *id);
+        let recovered = runs
+            .iter()
+            .position(|(_, passed)| !passed)
+            .is_some_and(|first_failure| runs[first_failure..].iter().any(|(_, passed)| *passed));
+        if recovered {
+            flaky.entry(name).or_default().push(sha);
+        }
+    }
+    for shas in flaky.values_mut() {
+        shas.sort();
+    }
+    flaky
+}
+
+// Nearest-rank percentile, None when there is nothing to measure
+fn percentile(values: &[f64], percent: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((percent / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn format_seconds(seconds: Option<f64>) -> String {
+    match seconds {
+        Some(seconds) => {
+            let seconds = seconds.round() as u64;
+            if seconds >= 60 {
+                format!("{}m {}s", seconds / 60, seconds % 60)
+            } else {
+                format!("{}s", seconds)
+            }
+        }
+        None => String::from("-"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_the_nearest_rank() {
+        let values = [5.0, 1.0, 4.0, 2.0, 3.0];
+        assert_eq!(percentile(&values, 50.0), Some(3.0));
+        assert_eq!(percentile(&values, 95.0), Some(5.0));
+        assert_eq!(percentile(&values, 0.0), Some(1.0));
+        assert_eq!(percentile(&[7.0], 95.0), Some(7.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn flaky_jobs_need_a_pass_after_a_failure() {
+        let attempt = |sha: &str, name: &str| (sha.to_string(), name.to_string());
+        let mut attempts = HashMap::new();
+        // Out of order on purpose, attempts are sorted by job ID
+        attempts.insert(attempt("aaa", "test"), vec![(3, true), (2, false)]);
+        attempts.insert(attempt("bbb", "test"), vec![(5, false), (6, true), (7, false)]);
+        attempts.insert(attempt("ccc", "test"), vec![(8, true), (9, false)]);
+        attempts.insert(attempt("aaa", "lint"), vec![(1, true)]);
+        attempts.insert(attempt("aaa", "build"), vec![(4, false), (10, false)]);
+
+        let flaky = flaky_jobs(attempts);
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky["test"], vec!["aaa".to_string(), "bbb".to_string()]);
+    }
+
+    #[test]
+    fn format_seconds_switches_to_minutes() {
+        assert_eq!(format_seconds(Some(59.4)), "59s");
+        assert_eq!(format_seconds(Some(125.0)), "2m 5s");
+        assert_eq!(format_seconds(None), "-");
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::Value;
+
+use crate::client::API_CLIENT;
+use crate::projects::fetch_project_id_by_name;
+
+// Show the parsed JUnit results of a pipeline, or what changed compared to another pipeline
+pub async fn show_test_report(
+    token: &str,
+    project_name: &str,
+    pipeline_id: i64,
+    failed_only: bool,
+    compare: Option<i64>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let report = fetch_test_report(token, project_id, pipeline_id)?;
+
+    match compare {
+        Some(base_id) => {
+            let base = fetch_test_report(token, project_id, base_id)?;
+            print_comparison(&base, base_id, &report, pipeline_id);
+        }
+        None => print_report(&report, pipeline_id, failed_only),
+    }
+    Ok(())
+}
+
+fn fetch_test_report(token: &str, project_id: u64, pipeline_id: i64) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}/test_report", project_id, pipeline_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
+    } else {
+        let error_message = format!("Failed to fetch the test report of pipeline {}: {}", pipeline_id, response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+fn print_report(report: &Value, pipeline_id: i64, failed_only: bool) {
+    let suites = report["test_suites"].as_array().cloned().unwrap_or_default();
+    if suites.is_empty() {
+        println!("Pipeline {} has no test report.", pipeline_id);
+        return;
+    }
+
+    println!(
+        "{} for pipeline {}: {} tests, {} passed, {} failed, {} errors, {} skipped in {:.2}s",
+        "Test report".bold(),
+        pipeline_id,
+        report["total_count"],
+        report["success_count"],
+        report["failed_count"],
+        report["error_count"],
+        report["skipped_count"],
+        report["total_time"].as_f64().unwrap_or_default()
+    );
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Suite").add_attribute(Attribute::Bold),
+            Cell::new("Total").add_attribute(Attribute::Bold),
+            Cell::new("Passed").add_attribute(Attribute::Bold),
+            Cell::new("Failed").add_attribute(Attribute::Bold),
+            Cell::new("Skipped").add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+        ]);
+    for suite in &suites {
+        let failures = count(suite, "failed_count") + count(suite, "error_count");
+        if failed_only && failures == 0 {
+            continue;
+        }
+        let failed_cell = if failures > 0 {
+            Cell::new(failures.to_string()).fg(Color::Red)
+        } else {
+            Cell::new("0")
+        };
+        table.add_row(vec![
+            Cell::new(suite["name"].as_str().unwrap_or("")),
+            Cell::new(count(suite, "total_count").to_string()),
+            Cell::new(count(suite, "success_count").to_string()).fg(Color::Green),
+            failed_cell,
+            Cell::new(count(suite, "skipped_count").to_string()),
+            Cell::new(format!("{:.2}s", suite["total_time"].as_f64().unwrap_or_default())),
+        ]);
+    }
+    println!("{table}");
+
+    let cases: Vec<(&str, &Value)> = suites
+        .iter()
+        .flat_map(|suite| {
+            let suite_name = suite["name"].as_str().unwrap_or("");
+            suite["test_cases"].as_array().into_iter().flatten().map(move |case| (suite_name, case))
+        })
+        .collect();
+    if !failed_only {
+        println!("\n{}", "Tests".bold());
+        for (suite_name, case) in &cases {
+            let status = case["status"].as_str().unwrap_or("");
+            let status = match status {
+                "success" => "passed".green(),
+                "skipped" => status.yellow(),
+                status if is_failing(status) => status.red(),
+                status => status.normal(),
+            };
+            println!(
+                "  {} {} ({}) {:.2}s",
+                status,
+                case_name(case),
+                suite_name,
+                case["execution_time"].as_f64().unwrap_or_default()
+            );
+        }
+    }
+
+    let failed: Vec<&(&str, &Value)> = cases.iter().filter(|(_, case)| is_failure(case)).collect();
+    if failed.is_empty() {
+        println!("\nNo failed tests.");
+        return;
+    }
+
+    println!("\n{}", "Failed tests".bold());
+    for (suite_name, case) in failed {
+        println!(
+            "\n{} {} ({})",
+            case["status"].as_str().unwrap_or("").red(),
+            case_name(case).bold(),
+            suite_name
+        );
+        if let Some(file) = case["file"].as_str() {
+            println!("File \"{}\"", file);
+        }
+        if let Some(output) = case["system_output"].as_str().filter(|output| !output.trim().is_empty()) {
+            println!("{}", output.trim_end());
+        }
+        if let Some(stack_trace) = case["stack_trace"].as_str().filter(|trace| !trace.trim().is_empty()) {
+            println!("{}", stack_trace.trim_end().dimmed());
+        }
+    }
+}
+
+struct Comparison {
+    newly_failing: Vec<String>,
+    newly_fixed: Vec<String>,
+    still_failing: usize,
+}
+
+fn print_comparison(base: &Value, base_id: i64, report: &Value, pipeline_id: i64) {
+    let Comparison { newly_failing, newly_fixed, still_failing } = compare_reports(base, report);
+
+    println!(
+        "{} pipeline {} against pipeline {}: {} newly failing, {} newly fixed, {} still failing",
+        "Comparing".bold(),
+        pipeline_id,
+        base_id,
+        newly_failing.len(),
+        newly_fixed.len(),
+        still_failing
+    );
+    if !newly_failing.is_empty() {
+        println!("\n{}", "Newly failing".bold());
+        for key in newly_failing {
+            println!("  {} {}", "✗".red(), key);
+        }
+    }
+    if !newly_fixed.is_empty() {
+        println!("\n{}", "Newly fixed".bold());
+        for key in newly_fixed {
+            println!("  {} {}", "✓".green(), key);
+        }
+    }
+}
+
+fn compare_reports(base: &Value, report: &Value) -> Comparison {
+    let before = case_statuses(base);
+    let after = case_statuses(report);
+    let was_failing = |key: &String| before.get(key).is_some_and(|status| is_failing(status));
+
+    Comparison {
+        newly_failing: after
+            .iter()
+            .filter(|(key, status)| is_failing(status) && !was_failing(key))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        newly_fixed: after
+            .iter()
+            .filter(|(key, status)| status.as_str() == "success" && was_failing(key))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        still_failing: after.iter().filter(|(key, status)| is_failing(status) && was_failing(key)).count(),
+    }
+}
+
+// "suite: classname.name" to the status of that test case. Parametrised tests
+// can report the same name several times, later ones get a " [2]", " [3]" suffix.
+fn case_statuses(report: &Value) -> BTreeMap<String, String> {
+    let mut statuses = BTreeMap::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for suite in report["test_suites"].as_array().into_iter().flatten() {
+        let suite_name = suite["name"].as_str().unwrap_or("");
+        for case in suite["test_cases"].as_array().into_iter().flatten() {
+            let key = format!("{}: {}", suite_name, case_name(case));
+            let occurrence = seen.entry(key.clone()).or_default();
+            *occurrence += 1;
+            let key = match *occurrence {
+                1 => key,
+                occurrence => format!("{} [{}]", key, occurrence),
+            };
+            statuses.insert(key, case["status"].as_str().unwrap_or("").to_string());
+        }
+    }
+    statuses
+}
+
+fn case_name(case: &Value) -> String {
+    match case["classname"].as_str().filter(|classname| !classname.is_empty()) {
+        Some(classname) => format!("{}.{}", classname, case["name"].as_str().unwrap_or("")),
+        None => case["name"].as_str().unwrap_or("").to_string(),
+    }
+}
+
+fn is_failure(case: &Value) -> bool {
+    is_failing(case["status"].as_str().unwrap_or(""))
+}
+
+fn is_failing(status: &str) -> bool {
+    status == "failed" || status == "error"
+}
+
+fn count(suite: &Value, field: &str) -> u64 {
+    suite[field].as_u64().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report(cases: &[(&str, &str, &str)]) -> Value {
+        json!({
+            "test_suites": [{
+                "name": "unit",
+                "test_cases": cases
+                    .iter()
+                    .map(|(classname, name, status)| json!({ "classname": classname, "name": name, "status": status }))
+                    .collect::<Vec<Value>>(),
+            }],
+        })
+    }
+
+    #[test]
+    fn case_statuses_keep_duplicate_names_apart() {
+        let statuses = case_statuses(&report(&[
+            ("tests.Math", "test_add", "success"),
+            ("tests.Math", "test_add", "failed"),
+            ("", "test_plain", "skipped"),
+        ]));
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses["unit: tests.Math.test_add"], "success");
+        assert_eq!(statuses["unit: tests.Math.test_add [2]"], "failed");
+        assert_eq!(statuses["unit: test_plain"], "skipped");
+    }
+
+    #[test]
+    fn compare_reports_splits_new_fixed_and_old_failures() {
+        let base = report(&[
+            ("a", "fixed", "failed"),
+            ("a", "broken", "success"),
+            ("a", "still", "error"),
+            ("a", "param", "success"),
+            ("a", "param", "success"),
+        ]);
+        let head = report(&[
+            ("a", "fixed", "success"),
+            ("a", "broken", "failed"),
+            ("a", "still", "failed"),
+            ("a", "param", "success"),
+            ("a", "param", "failed"),
+            ("a", "added", "failed"),
+        ]);
+
+        let comparison = compare_reports(&base, &head);
+        assert_eq!(
+            comparison.newly_failing,
+            vec!["unit: a.added", "unit: a.broken", "unit: a.param [2]"]
+        );
+        assert_eq!(comparison.newly_fixed, vec!["unit: a.fixed"]);
+        assert_eq!(comparison.still_failing, 1);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::client::API_CLIENT;
+use crate::client::{fetch_all_pages, API_CLIENT};
 use std::error::Error;
-use serde_json::Value;
+use serde_json::{json, Value};
+use crate::confirm::confirm_destructive;
 use crate::projects::fetch_project_id_by_name;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use comfy_table::{Table, Cell, presets::UTF8_FULL, Color, ContentArrangement}; // Import required modules
 use comfy_table::Attribute; // Import Attribute for cell styling
 use colored::Colorize;
@@ -13,18 +14,26 @@ const RESET: &str = "\x1b[0m";
 const CHECKMARK: &str = "✅";
 const CROSS: &str = "❌";
 
+// Reports that fetch details for each pipeline in a window only examine the most recent ones
+pub const MAX_PIPELINES: usize = 200;
+
 pub async fn fetch_pipelines_for_project(
     token: &str,
     project_name: &str,
     last: Option<String>,
     branch: Option<String>,
     show_jobs: bool,
+    depth: usize,
+    coverage: bool,
 ) -> Result<(), Box<dyn Error>> {
     let id = fetch_project_id_by_name(token, project_name).await?;
-    let response = API_CLIENT
-        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines/?ref={}", id, branch.unwrap().to_string()))
-        .header("Authorization", format!("Bearer {}", token))
-        .send()?;
+    let mut request = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines/", id))
+        .header("Authorization", format!("Bearer {}", token));
+    if let Some(branch) = &branch {
+        request = request.query(&[("ref", branch)]);
+    }
+    let response = request.send()?;
 
     if response.status().is_success() {
         let pipelines: Vec<Value> = response.json()?;
@@ -37,78 +46,30 @@ pub async fn fetch_pipelines_for_project(
             pipelines
         };
 
+        if !show_jobs {
+            println!("{}", pipelines_table(token, id, &pipelines_to_display, coverage));
+            return Ok(());
+        }
+
         // Iterate through each pipeline to display
         for pipeline in pipelines_to_display {
             // Colorize the status for console output
-            if let Some(status) = pipeline.get("status").and_then(Value::as_str) {
-                let colored_status = match status {
-                    "success" => format!("{}{} success{}", GREEN, CHECKMARK, RESET),
-                    "failed" => format!("{}{} failed{}", RED, CROSS, RESET),
-                    _ => status.to_string(), // Fallback for other statuses
-                };
-
-                // Print the pipeline details
-                println!("\nPipeline ID {}: {}", pipeline["id"], colored_status);
-                println!("Triggered at \"{}\" on \"{}\" branch", pipeline["created_at"], pipeline["ref"]);
-                println!("Web URL \"{}\"", pipeline["web_url"]);
-
-                // If show_jobs is true, fetch and print the jobs array
+            if pipeline.get("status").and_then(Value::as_str).is_some() {
+                print_pipeline(&pipeline);
+
+                // If show_jobs is true, fetch and print the jobs and any downstream pipelines
                 if show_jobs {
                     if let Some(pipeline_id) = pipeline.get("id").and_then(Value::as_i64) {
-                        let jobs_url = format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}/jobs", id, pipeline_id);
-                        let jobs_response = API_CLIENT
-                            .get(&jobs_url)
-                            .header("Authorization", format!("Bearer {}", token))
-                            .send()?;
-
-                        if jobs_response.status().is_success() {
-                            let jobs: Vec<Value> = jobs_response.json()?;
-
-                            // Create a comfy-table instance to display job details
-
-                            // Create a comfy-table instance to display job details
-                            let mut table = Table::new();
-                            table.load_preset(UTF8_FULL)
-                                .set_content_arrangement(ContentArrangement::Dynamic)
-                                // Use UTF8_FULL for solid lines
-                                .set_header(vec![
-                                    Cell::new("Job ID").add_attribute(Attribute::Bold),
-                                    Cell::new("Name").add_attribute(Attribute::Bold),
-                                    Cell::new("Stage").add_attribute(Attribute::Bold),
-                                    Cell::new("Status").add_attribute(Attribute::Bold),
-                                    Cell::new("Ref").add_attribute(Attribute::Bold),
-                                    Cell::new("Duration").add_attribute(Attribute::Bold),
-                                    Cell::new("Created At").add_attribute(Attribute::Bold),
-                                    Cell::new("Finished At").add_attribute(Attribute::Bold),
-                                    Cell::new("Web URL").add_attribute(Attribute::Bold),
-
-                                ]);
-
-                            // Add job details to the table
-                            for job in jobs {
-                                let status_cell = if job["status"].as_str() == Some("success") {
-                                    Cell::new(job["status"].as_str().unwrap_or("")).fg(Color::Green) // Green for success
-                                } else {
-                                    Cell::new(job["status"].as_str().unwrap_or("")).fg(Color::Red) // Red for failed
-                                };
-                                table.add_row(vec![
-                                    Cell::new(&job["id"].as_i64().unwrap_or_default().to_string()),
-                                    Cell::new(job["name"].as_str().unwrap_or("")),
-                                    Cell::new(job["stage"].as_str().unwrap_or("")),
-                                    status_cell,
-                                    Cell::new(job["ref"].as_str().unwrap_or("")),
-                                    Cell::new(&job["duration"].as_f64().unwrap_or_default().to_string()),
-                                    Cell::new(job["created_at"].as_str().unwrap_or("")),
-                                    Cell::new(job["finished_at"].as_str().unwrap_or("")),
-                                    Cell::new(job["web_url"].as_str().unwrap_or("")),
-
-                                ]);
+                        match fetch_pipeline_tree(token, id, pipeline_id, depth) {
+                            Ok(tree) => {
+                                let status = pipeline["status"].as_str().unwrap_or("");
+                                let overall = overall_status(status, &tree);
+                                if overall != status {
+                                    println!("Overall status with downstream pipelines: {}", colorize_status(&overall));
+                                }
+                                print_pipeline_tree(&tree, 0);
                             }
-                            // Print the table with a nice format
-                            println!("{table}");
-
-                        } else {
-                            eprintln!("Failed to fetch jobs for pipeline {}: {}", pipeline_id, jobs_response.status());
+                            Err(e) => eprintln!("Failed to fetch jobs for pipeline {}: {}", pipeline_id, e),
                         }
                     }
                 }
@@ -128,6 +89,22 @@ pub async fn fetch_pipelines_for_project(
 pub async fn fetch_job_logs(token: &str, project_name: &str, job_id: i64) -> Result<(), Box<dyn Error>> {
     // Fetch the project ID by name (implement this function as needed)
     let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let job_trace = fetch_job_trace(token, project_id, job_id)?;
+
+    // Print the job trace with color based on content
+    for line in job_trace.lines() {
+        if line.contains("ERROR") {
+            println!("{}", line.red()); // Red for errors
+        } else if line.contains("WARNING") {
+            println!("{}", line.yellow()); // Yellow for warnings
+        } else {
+            println!("{}", line.green()); // Green for regular logs
+        }
+    }
+    Ok(())
+}
+
+pub fn fetch_job_trace(token: &str, project_id: u64, job_id: i64) -> Result<String, Box<dyn Error>> {
     // Set up the API client and make the GET request
     let response = API_CLIENT
         .get(format!(
@@ -139,42 +116,375 @@ pub async fn fetch_job_logs(token: &str, project_name: &str, job_id: i64) -> Res
 
     // Check if the response is successful
     if response.status().is_success() {
-        let job_trace = response.text()?;
-        // Print the job trace with color based on content
-        for line in job_trace.lines() {
-            if line.contains("ERROR") {
-                println!("{}", line.red()); // Red for errors
-            } else if line.contains("WARNING") {
-                println!("{}", line.yellow()); // Yellow for warnings
-            } else {
-                println!("{}", line.green()); // Green for regular logs
+        Ok(response.text()?)
+    } else {
+        // If the response isn't successful, return an error message
+        let error_message = format!("Failed to fetch job trace: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+
+pub async fn delete_pipeline(token: &str, project_name: &str, pipeline_id: i64, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete pipeline {} in project `{}`", pipeline_id, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}", project_id, pipeline_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Pipeline {} deleted successfully.", pipeline_id);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete pipeline: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn erase_job(token: &str, project_name: &str, job_id: i64, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("erase the log and artifacts of job {} in project `{}`", job_id, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/jobs/{}/erase", project_id, job_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Job {} erased successfully.", job_id);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to erase job: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn trigger_pipeline(token: &str, project_name: &str, branch: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/pipeline", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "ref": branch }))
+        .send()?;
+
+    if response.status().is_success() {
+        let pipeline: Value = response.json()?;
+        print_pipeline(&pipeline);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to trigger pipeline: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+// A pipeline's jobs and trigger jobs, with the downstream pipelines the
+// trigger jobs started as (bridge, tree) pairs
+struct PipelineTree {
+    jobs: Vec<Value>,
+    bridges: Vec<Value>,
+    children: Vec<(Value, PipelineTree)>,
+}
+
+// Follow trigger jobs into child and multi-project pipelines, `depth` levels deep
+fn fetch_pipeline_tree(token: &str, project_id: u64, pipeline_id: i64, depth: usize) -> Result<PipelineTree, Box<dyn Error>> {
+    let jobs = fetch_pipeline_jobs(token, project_id, pipeline_id, false)?;
+    let bridges = fetch_pipeline_bridges(token, project_id, pipeline_id)?;
+
+    let mut children = Vec::new();
+    if depth > 0 {
+        for bridge in &bridges {
+            let downstream = &bridge["downstream_pipeline"];
+            let (Some(child_project_id), Some(child_id)) = (downstream["project_id"].as_u64(), downstream["id"].as_i64()) else {
+                continue;
+            };
+            match fetch_pipeline_tree(token, child_project_id, child_id, depth - 1) {
+                Ok(tree) => children.push((bridge.clone(), tree)),
+                Err(e) => eprintln!("Failed to fetch downstream pipeline {}: {}", child_id, e),
             }
         }
+    }
+    Ok(PipelineTree { jobs, bridges, children })
+}
 
-        Ok(())
+// The worst status across a pipeline and everything it triggered, since a parent
+// pipeline can succeed while one of its children failed
+fn overall_status(status: &str, tree: &PipelineTree) -> String {
+    let mut overall = status.to_string();
+    for bridge in &tree.bridges {
+        let downstream = &bridge["downstream_pipeline"];
+        let downstream_status = downstream["status"].as_str().unwrap_or("");
+        let child = tree
+            .children
+            .iter()
+            .find(|(child_bridge, _)| child_bridge["downstream_pipeline"]["id"] == downstream["id"]);
+        let child_status = match child {
+            Some((_, child)) => overall_status(downstream_status, child),
+            None => downstream_status.to_string(),
+        };
+        if status_severity(&child_status) > status_severity(&overall) {
+            overall = child_status;
+        }
+    }
+    overall
+}
+
+fn status_severity(status: &str) -> u8 {
+    match status {
+        "failed" => 4,
+        "canceled" => 3,
+        "running" => 2,
+        "pending" | "created" | "preparing" | "waiting_for_resource" | "scheduled" => 1,
+        _ => 0,
+    }
+}
+
+fn print_pipeline_tree(tree: &PipelineTree, level: usize) {
+    let indent = "    ".repeat(level);
+    for line in jobs_table(&tree.jobs, &tree.bridges).to_string().lines() {
+        println!("{}{}", indent, line);
+    }
+    for (bridge, child) in &tree.children {
+        let downstream = &bridge["downstream_pipeline"];
+        let status = overall_status(downstream["status"].as_str().unwrap_or(""), child);
+        println!(
+            "{}└─ Downstream pipeline ID {}: {} (triggered by {})",
+            indent,
+            downstream["id"],
+            colorize_status(&status),
+            bridge["name"].as_str().unwrap_or("")
+        );
+        println!("{}   Web URL \"{}\"", indent, downstream["web_url"].as_str().unwrap_or(""));
+        print_pipeline_tree(child, level + 1);
+    }
+}
+
+fn pipelines_table(token: &str, project_id: u64, pipelines: &[Value], coverage: bool) -> Table {
+    let mut header = vec![
+        Cell::new("Pipeline ID").add_attribute(Attribute::Bold),
+        Cell::new("Status").add_attribute(Attribute::Bold),
+        Cell::new("Ref").add_attribute(Attribute::Bold),
+        Cell::new("Created At").add_attribute(Attribute::Bold),
+        Cell::new("Web URL").add_attribute(Attribute::Bold),
+    ];
+    if coverage {
+        header.insert(4, Cell::new("Coverage").add_attribute(Attribute::Bold));
+    }
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+
+    for pipeline in pipelines {
+        let status = pipeline["status"].as_str().unwrap_or("");
+        let status_cell = match status {
+            "success" => Cell::new(status).fg(Color::Green),
+            "failed" => Cell::new(status).fg(Color::Red),
+            _ => Cell::new(status),
+        };
+        let mut row = vec![
+            Cell::new(pipeline["id"].as_i64().unwrap_or_default().to_string()),
+            status_cell,
+            Cell::new(pipeline["ref"].as_str().unwrap_or("")),
+            Cell::new(pipeline["created_at"].as_str().unwrap_or("")),
+            Cell::new(pipeline["web_url"].as_str().unwrap_or("")),
+        ];
+        if coverage {
+            // Coverage is only returned by the single pipeline endpoint
+            let pipeline_id = pipeline["id"].as_i64().unwrap_or_default();
+            let value = match fetch_pipeline(token, project_id, pipeline_id) {
+                Ok(details) => parse_coverage(&details["coverage"]),
+                Err(e) => {
+                    eprintln!("Failed to fetch the coverage of pipeline {}: {}", pipeline_id, e);
+                    None
+                }
+            };
+            row.insert(4, Cell::new(value.map(|coverage| format!("{:.2}%", coverage)).unwrap_or_else(|| String::from("-"))));
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+fn jobs_table(jobs: &[Value], bridges: &[Value]) -> Table {
+    // Create a comfy-table instance to display job details
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        // Use UTF8_FULL for solid lines
+        .set_header(vec![
+            Cell::new("Job ID").add_attribute(Attribute::Bold),
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Stage").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+            Cell::new("Ref").add_attribute(Attribute::Bold),
+            Cell::new("Duration").add_attribute(Attribute::Bold),
+            Cell::new("Coverage").add_attribute(Attribute::Bold),
+            Cell::new("Created At").add_attribute(Attribute::Bold),
+            Cell::new("Finished At").add_attribute(Attribute::Bold),
+            Cell::new("Runner").add_attribute(Attribute::Bold),
+            Cell::new("Web URL").add_attribute(Attribute::Bold),
+
+        ]);
+
+    // Add job details to the table, trigger jobs have no runner
+    for job in jobs.iter().chain(bridges) {
+        let status_cell = if job["status"].as_str() == Some("success") {
+            Cell::new(job["status"].as_str().unwrap_or("")).fg(Color::Green) // Green for success
+        } else {
+            Cell::new(job["status"].as_str().unwrap_or("")).fg(Color::Red) // Red for failed
+        };
+        table.add_row(vec![
+            Cell::new(job["id"].as_i64().unwrap_or_default().to_string()),
+            Cell::new(job["name"].as_str().unwrap_or("")),
+            Cell::new(job["stage"].as_str().unwrap_or("")),
+            status_cell,
+            Cell::new(job["ref"].as_str().unwrap_or("")),
+            Cell::new(job["duration"].as_f64().unwrap_or_default().to_string()),
+            Cell::new(parse_coverage(&job["coverage"]).map(|coverage| format!("{:.2}%", coverage)).unwrap_or_else(|| String::from("-"))),
+            Cell::new(job["created_at"].as_str().unwrap_or("")),
+            Cell::new(job["finished_at"].as_str().unwrap_or("")),
+            Cell::new(describe_runner(job)),
+            Cell::new(job["web_url"].as_str().unwrap_or("")),
+
+        ]);
+    }
+    table
+}
+
+// Describe the runner that picked up a job, pending jobs have none yet
+fn describe_runner(job: &Value) -> String {
+    let runner = &job["runner"];
+    if runner.is_null() {
+        return String::from("-");
+    }
+    format!("#{} {}", runner["id"], runner["description"].as_str().unwrap_or(""))
+}
+
+// Colorize a pipeline or job status for console output
+pub fn colorize_status(status: &str) -> String {
+    match status {
+        "success" => format!("{}{} success{}", GREEN, CHECKMARK, RESET),
+        "failed" => format!("{}{} failed{}", RED, CROSS, RESET),
+        _ => status.to_string(), // Fallback for other statuses
+    }
+}
+
+// Print the summary lines shared by `pipelines list`, `pipelines trigger` and `schedules run`
+pub fn print_pipeline(pipeline: &Value) {
+    let colored_status = colorize_status(pipeline["status"].as_str().unwrap_or(""));
+
+    // Print the pipeline details
+    println!("\nPipeline ID {}: {}", pipeline["id"], colored_status);
+    println!("Triggered at \"{}\" on \"{}\" branch", pipeline["created_at"], pipeline["ref"]);
+    println!("Web URL \"{}\"", pipeline["web_url"]);
+}
+
+// Pipelines report coverage as a string and jobs as a number, either may be null
+pub fn parse_coverage(coverage: &Value) -> Option<f64> {
+    match coverage {
+        Value::String(coverage) => coverage.parse().ok(),
+        _ => coverage.as_f64(),
+    }
+}
+
+pub async fn retry_job_by_project(token: &str, project_name: &str, job_id: i64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let job = retry_job(token, project_id, job_id)?;
+    println!("Job {} was retried as job {} ({})", job_id, job["id"], job["web_url"].as_str().unwrap_or(""));
+    Ok(())
+}
+
+pub async fn play_job_by_project(token: &str, project_name: &str, job_id: i64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let job = play_job(token, project_id, job_id)?;
+    println!("Job {} was started ({})", job["id"], job["web_url"].as_str().unwrap_or(""));
+    Ok(())
+}
+
+// Retry a finished job, returning the newly created job
+pub fn retry_job(token: &str, project_id: u64, job_id: i64) -> Result<Value, Box<dyn Error>> {
+    job_action(token, project_id, job_id, "retry")
+}
+
+// Start a manual job, returning the job
+pub fn play_job(token: &str, project_id: u64, job_id: i64) -> Result<Value, Box<dyn Error>> {
+    job_action(token, project_id, job_id, "play")
+}
+
+fn job_action(token: &str, project_id: u64, job_id: i64, action: &str) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/jobs/{}/{}", project_id, job_id, action))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
     } else {
-        // If the response isn't successful, return an error message
-        let error_message = format!("Failed to fetch job trace: {}", response.status());
+        let error_message = format!("Failed to {} job {}: {}", action, job_id, response.text()?);
         Err(Box::from(error_message))
     }
 }
 
+pub fn fetch_pipeline(token: &str, project_id: u64, pipeline_id: i64) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}", project_id, pipeline_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
+    } else {
+        let error_message = format!("Failed to fetch pipeline {}: {}", pipeline_id, response.status());
+        Err(Box::from(error_message))
+    }
+}
 
-fn parse_duration(last: &str) -> Result<Duration, Box<dyn Error>> {
-    let num_str = &last[..last.len() - 1]; // Get the numeric part
+// Fetch every job of a pipeline, including retried ones when asked
+pub fn fetch_pipeline_jobs(token: &str, project_id: u64, pipeline_id: i64, include_retried: bool) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut jobs_url = format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}/jobs", project_id, pipeline_id);
+    if include_retried {
+        jobs_url.push_str("?include_retried=true");
+    }
+    fetch_all_pages(token, &jobs_url)
+}
+
+// Fetch the trigger jobs of a pipeline, which the jobs endpoint leaves out
+pub fn fetch_pipeline_bridges(token: &str, project_id: u64, pipeline_id: i64) -> Result<Vec<Value>, Box<dyn Error>> {
+    fetch_all_pages(
+        token,
+        &format!("https://gitlab.com/api/v4/projects/{}/pipelines/{}/bridges", project_id, pipeline_id),
+    )
+}
+
+pub fn parse_duration(last: &str) -> Result<Duration, Box<dyn Error>> {
     let unit = last.chars().last().ok_or("Invalid format: No unit provided")?; // Get the last character as the unit
+    let num_str = &last[..last.len() - unit.len_utf8()]; // Get the numeric part
 
     // Try to parse the numeric part
     let num: usize = num_str.parse().map_err(|_| Box::<dyn Error>::from("Invalid number format"))?;
+    let num = i64::try_from(num).map_err(|_| Box::<dyn Error>::from("Duration is too long"))?;
 
-    match unit {
-        'h' => Ok(Duration::hours(num as i64)),
-        'd' => Ok(Duration::days(num as i64)),
-        _ => Err(Box::<dyn Error>::from("Invalid time unit, use 'h' for hours or 'd' for days.")),
-    }
+    let duration = match unit {
+        'h' => Duration::try_hours(num),
+        'd' => Duration::try_days(num),
+        _ => return Err(Box::<dyn Error>::from("Invalid time unit, use 'h' for hours or 'd' for days.")),
+    };
+    duration.ok_or_else(|| Box::<dyn Error>::from("Duration is too long"))
 }
 
-fn filter_pipelines(pipelines: Vec<Value>, duration: Duration) -> Vec<Value> {
+// Start of a `--last` window as an RFC 3339 timestamp for `updated_after`
+pub fn window_start(window: Duration) -> Result<String, Box<dyn Error>> {
+    Utc::now()
+        .checked_sub_signed(window)
+        .map(|since| since.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .ok_or_else(|| Box::<dyn Error>::from("Duration reaches back too far"))
+}
+
+pub fn filter_pipelines(pipelines: Vec<Value>, duration: Duration) -> Vec<Value> {
     let now: DateTime<Utc> = Utc::now();
     pipelines.into_iter()
         .filter(|pipeline| {
@@ -188,3 +498,44 @@ fn filter_pipelines(pipelines: Vec<Value>, duration: Duration) -> Vec<Value> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_reads_hours_and_days() {
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+    }
+
+    #[test]
+    fn parse_duration_rejects_bad_input() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7w").is_err());
+        assert!(parse_duration("7é").is_err());
+        assert!(parse_duration("é").is_err());
+    }
+
+    #[test]
+    fn window_start_rejects_windows_before_the_earliest_date() {
+        assert!(window_start(Duration::days(30)).is_ok());
+        assert!(window_start(Duration::days(100_000_000)).is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflowing_input() {
+        assert!(parse_duration("200000000000000d").is_err());
+        assert!(parse_duration("9223372036854775808h").is_err());
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn parse_coverage_accepts_strings_and_numbers() {
+        assert_eq!(parse_coverage(&json!("87.5")), Some(87.5));
+        assert_eq!(parse_coverage(&json!(92.25)), Some(92.25));
+        assert_eq!(parse_coverage(&json!(null)), None);
+        assert_eq!(parse_coverage(&json!("n/a")), None);
+    }
+}
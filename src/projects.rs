@@ -1,56 +1,153 @@
 use std::error::Error;
-use comfy_table::{Table, Cell, presets::UTF8_FULL, Color, ContentArrangement}; // Import required modules
-use comfy_table::Attribute; // Import Attribute for cell styling
-use serde_json::{json, to_string_pretty, Value};
-use serde_json::ser::CharEscape::Tab;
-use crate::client::API_CLIENT;
-// Fetch all GitLab projects asynchronously
-pub async fn fetch_all_gitlab_projects(token: &str) -> Result<Vec<Value>, Box<dyn Error>> {
-    let response = API_CLIENT
-        .get("https://gitlab.com/api/v4/groups/66653699/projects")
-        .header("Authorization", format!("Bearer {}", token))
-        .send()?; // Await the response
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, to_string_pretty, Map, Value};
+use crate::cache;
+use crate::client::{encode_path, fetch_all_pages, fetch_all_pages_with_query, API_CLIENT};
+use crate::commands::{OutputFormat, ProjectSort};
+use crate::confirm::confirm_destructive;
+use crate::pipelines::colorize_status;
 
-    if response.status().is_success() {
-        // Parse the response body as JSON
-        let projects: Value = response.json()?; // Await JSON parsing
-
-        // Filter and display only the required fields
-        let mut project_list = Vec::new(); // To store projects
-        if let Some(projects_array) = projects.as_array() {
-            for project in projects_array {
-                let id = project.get("id").unwrap_or(&json!(null));
-                let name = project.get("name").unwrap_or(&json!(null));
-                let http_url_to_repo = project.get("http_url_to_repo").unwrap_or(&json!(null));
-
-                // Print project details
-                println!("Project ID: {}", id);
-                println!("Name: {}", name);
-                println!("Repository URL: {}\n", http_url_to_repo);
-
-                // Store project in the list
-                project_list.push(project.clone());
+// Group whose projects are listed and searched when no other scope is given
+const DEFAULT_GROUP_ID: u64 = 66653699;
+
+// Filters accepted by `projects list`, passed straight through to the API
+pub struct ProjectListFilters {
+    pub search: Option<String>,
+    pub owned: bool,
+    pub membership: bool,
+    pub starred: bool,
+    pub archived: bool,
+    pub visibility: Option<String>,
+    pub topic: Option<String>,
+    pub language: Option<String>,
+    pub sort: ProjectSort,
+    pub group: Option<String>,
+    pub include_subgroups: bool,
+}
+
+// List projects in a group, or across groups with --owned, --membership or --starred
+pub async fn fetch_all_gitlab_projects(token: &str, filters: &ProjectListFilters) -> Result<Vec<Value>, Box<dyn Error>> {
+    let url = match &filters.group {
+        Some(group) => format!("https://gitlab.com/api/v4/groups/{}/projects", encode_path(group)),
+        None if filters.owned || filters.membership || filters.starred => String::from("https://gitlab.com/api/v4/projects"),
+        None => format!("https://gitlab.com/api/v4/groups/{}/projects", DEFAULT_GROUP_ID),
+    };
+
+    let (order_by, sort) = filters.sort.order();
+    let mut query: Vec<(&str, &str)> = vec![("order_by", order_by), ("sort", sort)];
+    for (name, enabled) in [
+        ("owned", filters.owned),
+        ("membership", filters.membership),
+        ("starred", filters.starred),
+        ("archived", filters.archived),
+        ("include_subgroups", filters.include_subgroups),
+    ] {
+        if enabled {
+            query.push((name, "true"));
+        }
+    }
+    for (name, value) in [
+        ("search", &filters.search),
+        ("visibility", &filters.visibility),
+        ("topic", &filters.topic),
+        ("with_programming_language", &filters.language),
+    ] {
+        if let Some(value) = value {
+            query.push((name, value.as_str()));
+        }
+    }
+    let projects = fetch_all_pages_with_query(token, &url, &query)?;
+    remember_project_ids(&projects);
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").add_attribute(Attribute::Bold),
+            Cell::new("Project").add_attribute(Attribute::Bold),
+            Cell::new("Visibility").add_attribute(Attribute::Bold),
+            Cell::new("Default Branch").add_attribute(Attribute::Bold),
+            Cell::new("Last Activity").add_attribute(Attribute::Bold),
+            Cell::new("Web URL").add_attribute(Attribute::Bold),
+        ]);
+    for project in &projects {
+        let mut path_cell = Cell::new(project["path_with_namespace"].as_str().unwrap_or(""));
+        if project["archived"].as_bool().unwrap_or(false) {
+            path_cell = path_cell.fg(Color::DarkGrey);
+        }
+        table.add_row(vec![
+            Cell::new(project["id"].to_string()),
+            path_cell,
+            Cell::new(project["visibility"].as_str().unwrap_or("")),
+            Cell::new(project["default_branch"].as_str().unwrap_or("-")),
+            Cell::new(project["last_activity_at"].as_str().unwrap_or("")),
+            Cell::new(project["web_url"].as_str().unwrap_or("")),
+        ]);
+    }
+    println!("{table}");
+    println!("{} project(s)", projects.len());
+    Ok(projects) // Return the list of projects
+}
+
+fn fetch_group_projects(token: &str) -> Result<Vec<Value>, Box<dyn Error>> {
+    let projects = fetch_all_pages(token, &format!("https://gitlab.com/api/v4/groups/{}/projects", DEFAULT_GROUP_ID))?;
+    remember_project_ids(&projects);
+    Ok(projects)
+}
+
+// One listing resolves every project in it, so remember all of them. Bare names
+// are looked up in the default group, so only its own projects are cached under
+// their name, anything else could shadow a project there.
+fn remember_project_ids(projects: &[Value]) {
+    let mut ids = Vec::new();
+    for project in projects {
+        let Some(id) = project["id"].as_u64() else {
+            continue;
+        };
+        if let Some(path) = project["path_with_namespace"].as_str() {
+            ids.push((path.to_string(), id));
+        }
+        if project["namespace"]["id"].as_u64() == Some(DEFAULT_GROUP_ID) {
+            if let Some(name) = project["name"].as_str() {
+                ids.push((name.to_string(), id));
             }
         }
-        return Ok(project_list); // Return the list of projects
-    } else {
-        println!("Failed to fetch projects: {}", response.status());
     }
-    Ok(Vec::new()) // Return an empty Vec if no projects found
+    cache::store_project_ids(&ids);
 }
 
 
-// Fetch a project by name asynchronously
+// Resolve a project name or full path to its ID, using the on-disk cache when possible
 
 pub async fn fetch_project_id_by_name(token: &str, project_name: &str) -> Result<u64, Box<dyn Error>> {
-    let projects = fetch_all_gitlab_projects(token).await?;
+    if let Some(id) = cache::cached_project_id(project_name) {
+        return Ok(id);
+    }
 
+    // Full paths such as `group/project` can be looked up directly
+    if project_name.contains('/') {
+        let response = API_CLIENT
+            .get(format!("https://gitlab.com/api/v4/projects/{}", encode_path(project_name)))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()?;
+        if response.status().is_success() {
+            let project: Value = response.json()?;
+            if let Some(id) = project["id"].as_u64() {
+                cache::store_project_ids(&[(project_name.to_string(), id)]);
+                return Ok(id);
+            }
+        }
+    }
+
+    let projects = fetch_group_projects(token)?;
     for project in projects {
-        if let Some(name) = project.get("name").and_then(Value::as_str) {
-            if name == project_name {
-                if let Some(id) = project.get("id").and_then(Value::as_u64) {
-                    return Ok(id); // Return the ID when found
-                }
+        let matches = project["name"].as_str() == Some(project_name)
+            || project["path_with_namespace"].as_str() == Some(project_name);
+        if matches {
+            if let Some(id) = project.get("id").and_then(Value::as_u64) {
+                return Ok(id); // Return the ID when found
             }
         }
     }
@@ -59,20 +156,316 @@ pub async fn fetch_project_id_by_name(token: &str, project_name: &str) -> Result
     Err(Box::from(format!("Project '{}' not found", project_name)))
 }
 
-pub async fn fetch_project_by_name(token: &str, project_name: String) -> Result<(), Box<dyn Error>> {
+pub async fn fetch_project_by_name(token: &str, project_name: String, output: OutputFormat) -> Result<(), Box<dyn Error>> {
     let id = fetch_project_id_by_name(token, &project_name).await?;
     let response = API_CLIENT
         .get(format!("https://gitlab.com/api/v4/projects/{}", id))
         .header("Authorization", format!("Bearer {}", token))
         .send()?;
+    if !response.status().is_success() {
+        // Handle the case where the request was not successful
+        let error_message = format!("Failed to fetch project details: {}", response.status());
+        return Err(Box::from(error_message)); // Return an error with a descriptive message
+    }
+    let project_info: Value = response.json()?; // Await JSON parsing
+    if output == OutputFormat::Json {
+        println!("{}", to_string_pretty(&project_info)?);
+        return Ok(());
+    }
+
+    let default_branch = project_info["default_branch"].as_str().unwrap_or("");
+    println!(
+        "{} [{}]",
+        project_info["path_with_namespace"].as_str().unwrap_or(&project_name).bold(),
+        project_info["visibility"].as_str().unwrap_or("")
+    );
+    if project_info["archived"].as_bool().unwrap_or(false) {
+        println!("{}", "This project is archived".yellow());
+    }
+    if let Some(description) = project_info["description"].as_str().filter(|text| !text.trim().is_empty()) {
+        println!("{}", description);
+    }
+    println!();
+    println!("Default branch: {}", default_branch);
+    println!("Stars: {}  Forks: {}", project_info["star_count"], project_info["forks_count"]);
+    println!(
+        "Open merge requests: {}  Open issues: {}",
+        count_open_merge_requests(token, id).map_or(String::from("-"), |count| count.to_string()),
+        project_info["open_issues_count"].as_u64().map_or(String::from("-"), |count| count.to_string())
+    );
+    // Projects with CI disabled have no pipelines endpoint, that should not hide the rest
+    match latest_pipeline(token, id, default_branch) {
+        Ok(Some(pipeline)) => println!(
+            "Latest pipeline on {}: ID {} {}",
+            default_branch,
+            pipeline["id"],
+            colorize_status(pipeline["status"].as_str().unwrap_or(""))
+        ),
+        Ok(None) => println!("Latest pipeline on {}: none", default_branch),
+        Err(_) => println!("Latest pipeline on {}: -", default_branch),
+    }
+    println!("Last activity at \"{}\"", project_info["last_activity_at"].as_str().unwrap_or(""));
+    println!();
+    println!("Web URL \"{}\"", project_info["web_url"].as_str().unwrap_or(""));
+    println!("Clone (HTTPS) \"{}\"", project_info["http_url_to_repo"].as_str().unwrap_or(""));
+    println!("Clone (SSH) \"{}\"", project_info["ssh_url_to_repo"].as_str().unwrap_or(""));
+    Ok(())
+}
+
+// The merge request list reports its size in the x-total or x-total-pages header
+fn count_open_merge_requests(token: &str, project_id: u64) -> Result<u64, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/merge_requests", project_id))
+        .query(&[("state", "opened"), ("per_page", "1")])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to count merge requests: {}", response.status())));
+    }
+    // GitLab drops x-total on very large lists, with one item per page the page count is the total
+    ["x-total", "x-total-pages"]
+        .iter()
+        .find_map(|header| response.headers().get(*header)?.to_str().ok()?.parse().ok())
+        .ok_or_else(|| Box::from("GitLab did not report the number of merge requests"))
+}
+
+fn latest_pipeline(token: &str, project_id: u64, reference: &str) -> Result<Option<Value>, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipelines", project_id))
+        .query(&[("ref", reference), ("per_page", "1")])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to fetch pipelines: {}", response.status())));
+    }
+    let pipelines: Vec<Value> = response.json()?;
+    Ok(pipelines.into_iter().next())
+}
+
+// Fetch every project the token's user is a member of, across all groups
+pub async fn fetch_member_projects(token: &str) -> Result<Vec<Value>, Box<dyn Error>> {
+    fetch_all_pages(token, "https://gitlab.com/api/v4/projects?membership=true&simple=true")
+}
+
+// Settings shown by `projects settings get` and accepted by `projects settings set`
+const PROJECT_SETTINGS: [&str; 14] = [
+    "description",
+    "visibility",
+    "default_branch",
+    "merge_method",
+    "squash_option",
+    "ci_config_path",
+    "only_allow_merge_if_pipeline_succeeds",
+    "only_allow_merge_if_all_discussions_are_resolved",
+    "remove_source_branch_after_merge",
+    "printing_merge_request_link_enabled",
+    "issues_access_level",
+    "merge_requests_access_level",
+    "builds_access_level",
+    "wiki_access_level",
+];
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_project(
+    token: &str,
+    name: &str,
+    path: Option<String>,
+    namespace: Option<String>,
+    visibility: Option<String>,
+    default_branch: Option<String>,
+    description: Option<String>,
+    template: Option<String>,
+    template_project_id: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let mut body = Map::new();
+    body.insert(String::from("name"), json!(name));
+    if let Some(path) = path {
+        body.insert(String::from("path"), json!(path));
+    }
+    if let Some(namespace) = namespace {
+        body.insert(String::from("namespace_id"), json!(fetch_namespace_id(token, &namespace)?));
+    }
+    if let Some(visibility) = visibility {
+        body.insert(String::from("visibility"), json!(visibility));
+    }
+    if let Some(default_branch) = default_branch {
+        body.insert(String::from("default_branch"), json!(default_branch));
+    }
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    // Built-in templates are referenced by name, custom ones by project ID
+    if let Some(template) = template {
+        body.insert(String::from("template_name"), json!(template));
+    }
+    if let Some(template_project_id) = template_project_id {
+        body.insert(String::from("template_project_id"), json!(template_project_id));
+        body.insert(String::from("use_custom_template"), json!(true));
+    }
+
+    let response = API_CLIENT
+        .post("https://gitlab.com/api/v4/projects")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
     if response.status().is_success() {
-        let project_info: Value = response.json()?; // Await JSON parsing
-        let formatted_response = to_string_pretty(&project_info)?; // Format the JSON response
-        println!("{}", formatted_response);
-        Ok(()) // Return Ok after successful printing
+        let project: Value = response.json()?;
+        println!("Project {} was created successfully", project["path_with_namespace"].as_str().unwrap_or(name));
+        println!("Repository URL: {}", project["http_url_to_repo"].as_str().unwrap_or(""));
+        Ok(())
     } else {
-        // Handle the case where the request was not successful
+        let error_message = format!("Failed to create project: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn fork_project(
+    token: &str,
+    project_name: &str,
+    namespace: Option<String>,
+    fork_name: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut body = Map::new();
+    if let Some(namespace) = namespace {
+        body.insert(String::from("namespace_path"), json!(namespace));
+    }
+    if let Some(fork_name) = fork_name {
+        body.insert(String::from("name"), json!(fork_name.clone()));
+        body.insert(String::from("path"), json!(fork_name));
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/fork", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        let fork: Value = response.json()?;
+        println!("Project {} was forked to {}", project_name, fork["path_with_namespace"].as_str().unwrap_or(""));
+        Ok(())
+    } else {
+        let error_message = format!("Failed to fork project: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+// Archive or unarchive a project, `action` is either "archive" or "unarchive"
+pub async fn set_project_archived(token: &str, project_name: &str, action: &str) -> Result<(), Box<dyn Error>> {
+    let id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/{}", id, action))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Project {} was {}d successfully", project_name, action);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to {} project: {}", action, response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_project(token: &str, project_name: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete project `{}` and all of its data", project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        cache::forget_project_id(id);
+        // GitLab may keep the project for a grace period before removing it
+        println!("Project {} was scheduled for deletion.", project_name);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete project: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn get_project_settings(token: &str, project_name: &str) -> Result<(), Box<dyn Error>> {
+    let id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
         let error_message = format!("Failed to fetch project details: {}", response.status());
-        Err(Box::from(error_message)) // Return an error with a descriptive message
+        return Err(Box::from(error_message));
+    }
+    let project: Value = response.json()?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_header(vec![
+            Cell::new("Setting").add_attribute(Attribute::Bold),
+            Cell::new("Value").add_attribute(Attribute::Bold),
+        ]);
+    for key in PROJECT_SETTINGS {
+        let value = match &project[key] {
+            Value::String(text) => text.clone(),
+            Value::Null => String::from("-"),
+            other => other.to_string(),
+        };
+        table.add_row(vec![Cell::new(key), Cell::new(value)]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn set_project_settings(token: &str, project_name: &str, settings: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut body = Map::new();
+    for setting in settings {
+        let (key, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid setting '{}', expected key=value", setting))?;
+        if !PROJECT_SETTINGS.contains(&key) {
+            return Err(Box::from(format!("Unknown setting '{}', supported settings: {}", key, PROJECT_SETTINGS.join(", "))));
+        }
+        // Pass booleans through as JSON booleans, everything else as strings
+        let value = match value {
+            "true" => json!(true),
+            "false" => json!(false),
+            _ => json!(value),
+        };
+        body.insert(key.to_string(), value);
     }
-   }
+
+    let id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .put(format!("https://gitlab.com/api/v4/projects/{}", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Settings for project {} were updated successfully", project_name);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to update project settings: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+fn fetch_namespace_id(token: &str, namespace: &str) -> Result<u64, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/namespaces/{}", encode_path(namespace)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Namespace '{}' not found: {}", namespace, response.status())));
+    }
+    let namespace_info: Value = response.json()?;
+    namespace_info["id"]
+        .as_u64()
+        .ok_or_else(|| Box::from(format!("Namespace '{}' has no ID", namespace)))
+}
@@ -0,0 +1,362 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+use serde_json::{json, Map, Value};
+
+use crate::client::{encode_path, fetch_all_pages, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::pipelines::{fetch_pipeline, fetch_pipeline_jobs};
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_releases(token: &str, project_name: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let releases = fetch_all_pages(token, &format!("https://gitlab.com/api/v4/projects/{}/releases", project_id))?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Tag").add_attribute(Attribute::Bold),
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Author").add_attribute(Attribute::Bold),
+            Cell::new("Milestones").add_attribute(Attribute::Bold),
+            Cell::new("Assets").add_attribute(Attribute::Bold),
+            Cell::new("Released At").add_attribute(Attribute::Bold),
+        ]);
+
+    for release in releases {
+        table.add_row(vec![
+            Cell::new(release["tag_name"].as_str().unwrap_or("")),
+            Cell::new(release["name"].as_str().unwrap_or("")),
+            Cell::new(release["author"]["username"].as_str().unwrap_or("")),
+            Cell::new(milestone_titles(&release).join(", ")),
+            Cell::new(release["assets"]["links"].as_array().map(Vec::len).unwrap_or_default().to_string()),
+            Cell::new(release["released_at"].as_str().unwrap_or("")),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn view_release(token: &str, project_name: &str, tag: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/releases/{}", project_id, encode_path(tag)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to fetch release {}: {}", tag, response.status());
+        return Err(Box::from(error_message));
+    }
+    let release: Value = response.json()?;
+
+    println!("{} ({})", release["name"].as_str().unwrap_or(tag).bold(), release["tag_name"].as_str().unwrap_or(tag));
+    println!(
+        "Released at \"{}\" by @{}",
+        release["released_at"].as_str().unwrap_or(""),
+        release["author"]["username"].as_str().unwrap_or("")
+    );
+    println!("Commit {}", release["commit"]["short_id"].as_str().unwrap_or(""));
+    let milestones = milestone_titles(&release);
+    if !milestones.is_empty() {
+        println!("Milestones: {}", milestones.join(", "));
+    }
+    println!("Web URL \"{}\"", release["_links"]["self"].as_str().unwrap_or(""));
+
+    if let Some(links) = release["assets"]["links"].as_array().filter(|links| !links.is_empty()) {
+        println!("\n{}", "Assets".bold());
+        for link in links {
+            println!("  {}: {}", link["name"].as_str().unwrap_or(""), link["url"].as_str().unwrap_or(""));
+        }
+    }
+
+    println!("\n{}", "Release notes".bold());
+    match release["description"].as_str().filter(|text| !text.trim().is_empty()) {
+        Some(description) => println!("{}", description),
+        None => println!("{}", "No release notes.".dimmed()),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_release(
+    token: &str,
+    project_name: &str,
+    tag: &str,
+    name: Option<String>,
+    reference: Option<String>,
+    notes: Option<String>,
+    notes_file: Option<PathBuf>,
+    changelog: Option<PathBuf>,
+    assets: Vec<String>,
+    milestones: Vec<String>,
+    from_pipeline: Option<i64>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let description = read_release_notes(tag, notes, notes_file, changelog)?;
+    let mut links = parse_asset_links(&assets)?;
+    let mut reference = reference;
+
+    // Attach the artifacts of every successful job and tag the pipeline's commit
+    if let Some(pipeline_id) = from_pipeline {
+        let pipeline = fetch_pipeline(token, project_id, pipeline_id)?;
+        let status = pipeline["status"].as_str().unwrap_or("");
+        if status != "success" {
+            return Err(Box::from(format!("Pipeline {} did not succeed (status: {})", pipeline_id, status)));
+        }
+        if reference.is_none() {
+            reference = pipeline["sha"].as_str().map(String::from);
+        }
+        for job in fetch_pipeline_jobs(token, project_id, pipeline_id, false)? {
+            if job["status"].as_str() != Some("success") || job["artifacts_file"].is_null() {
+                continue;
+            }
+            links.push(json!({
+                "name": format!("{} artifacts", job["name"].as_str().unwrap_or("job")),
+                "url": format!("{}/artifacts/download", job["web_url"].as_str().unwrap_or("")),
+                "link_type": "package",
+            }));
+        }
+    }
+
+    let mut body = Map::new();
+    body.insert(String::from("tag_name"), json!(tag));
+    if let Some(name) = name {
+        body.insert(String::from("name"), json!(name));
+    }
+    if let Some(reference) = reference {
+        body.insert(String::from("ref"), json!(reference));
+    }
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    if !milestones.is_empty() {
+        body.insert(String::from("milestones"), json!(milestones));
+    }
+    if !links.is_empty() {
+        body.insert(String::from("assets"), json!({ "links": links }));
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/releases", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Release {} was created successfully with {} asset link(s)", tag, links.len());
+        Ok(())
+    } else {
+        let error_message = format!("Failed to create release: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_release(
+    token: &str,
+    project_name: &str,
+    tag: &str,
+    name: Option<String>,
+    notes: Option<String>,
+    notes_file: Option<PathBuf>,
+    changelog: Option<PathBuf>,
+    assets: Vec<String>,
+    milestones: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let release_url = format!("https://gitlab.com/api/v4/projects/{}/releases/{}", project_id, encode_path(tag));
+    let description = read_release_notes(tag, notes, notes_file, changelog)?;
+    let links = parse_asset_links(&assets)?;
+
+    let mut body = Map::new();
+    if let Some(name) = name {
+        body.insert(String::from("name"), json!(name));
+    }
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    if !milestones.is_empty() {
+        body.insert(String::from("milestones"), json!(milestones));
+    }
+
+    if !body.is_empty() {
+        let response = API_CLIENT
+            .put(&release_url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&Value::Object(body))
+            .send()?;
+        if !response.status().is_success() {
+            let error_message = format!("Failed to update release: {}", response.text()?);
+            return Err(Box::from(error_message));
+        }
+    }
+
+    // Asset links can only be added one at a time after the release exists
+    let mut failed: Vec<String> = Vec::new();
+    for link in &links {
+        let response = API_CLIENT
+            .post(format!("{}/assets/links", release_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(link)
+            .send()?;
+        if !response.status().is_success() {
+            eprintln!("Failed to add asset link {}: {}", link["name"], response.text()?);
+            failed.push(link["name"].as_str().unwrap_or("").to_string());
+        }
+    }
+
+    if failed.is_empty() {
+        println!("Release {} was updated successfully", tag);
+        Ok(())
+    } else {
+        let error_message = format!(
+            "Release {} was updated, but {} asset link(s) could not be added: {}",
+            tag,
+            failed.len(),
+            failed.join(", ")
+        );
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_release(token: &str, project_name: &str, tag: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete release {} in project `{}` (the tag is kept)", tag, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/releases/{}", project_id, encode_path(tag)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Release {} deleted successfully.", tag);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete release: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+// Release notes come from --notes, a notes file, or the tag's section of a changelog
+fn read_release_notes(
+    tag: &str,
+    notes: Option<String>,
+    notes_file: Option<PathBuf>,
+    changelog: Option<PathBuf>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    if let Some(notes) = notes {
+        return Ok(Some(notes));
+    }
+    if let Some(path) = notes_file {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return Ok(Some(contents));
+    }
+    if let Some(path) = changelog {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        return extract_changelog_section(&contents, tag)
+            .map(Some)
+            .ok_or_else(|| Box::from(format!("No section for {} found in {}", tag, path.display())));
+    }
+    Ok(None)
+}
+
+// Take the lines under the first heading that mentions the version, up to the
+// next heading of the same or a higher level
+fn extract_changelog_section(changelog: &str, tag: &str) -> Option<String> {
+    let version = tag.trim_start_matches('v');
+    let mut section: Vec<&str> = Vec::new();
+    let mut section_level = None;
+
+    for line in changelog.lines() {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        match section_level {
+            Some(section_level) => {
+                if level > 0 && level <= section_level {
+                    break;
+                }
+                section.push(line);
+            }
+            None => {
+                let mentions_version = line
+                    .split(|c: char| !(c.is_alphanumeric() || c == '.' || c == '-'))
+                    .any(|word| word.trim_start_matches('v') == version);
+                if level > 0 && mentions_version {
+                    section_level = Some(level);
+                }
+            }
+        }
+    }
+
+    section_level.map(|_| section.join("\n").trim().to_string())
+}
+
+// Parse `name=url` pairs given with --asset
+fn parse_asset_links(assets: &[String]) -> Result<Vec<Value>, Box<dyn Error>> {
+    assets
+        .iter()
+        .map(|asset| match asset.split_once('=') {
+            Some((name, url)) => Ok(json!({ "name": name, "url": url })),
+            None => Err(Box::from(format!("Invalid asset '{}', expected name=url", asset))),
+        })
+        .collect()
+}
+
+fn milestone_titles(release: &Value) -> Vec<&str> {
+    release["milestones"]
+        .as_array()
+        .map(|milestones| milestones.iter().filter_map(|milestone| milestone["title"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+
+## [1.2.0] - 2024-05-01
+### Added
+- Release notes from the changelog
+
+### Fixed
+- Tag search
+
+## [1.2.0-rc1] - 2024-04-20
+- Preview
+
+## v1.1.0
+- First release
+";
+
+    #[test]
+    fn extracts_the_section_with_its_subheadings() {
+        assert_eq!(
+            extract_changelog_section(CHANGELOG, "v1.2.0").as_deref(),
+            Some("### Added\n- Release notes from the changelog\n\n### Fixed\n- Tag search")
+        );
+    }
+
+    #[test]
+    fn matches_versions_exactly_with_or_without_the_v_prefix() {
+        assert_eq!(extract_changelog_section(CHANGELOG, "1.2.0-rc1").as_deref(), Some("- Preview"));
+        assert_eq!(extract_changelog_section(CHANGELOG, "1.1.0").as_deref(), Some("- First release"));
+        assert_eq!(extract_changelog_section(CHANGELOG, "v1.2"), None);
+    }
+
+    #[test]
+    fn parses_asset_links() {
+        let links = parse_asset_links(&[String::from("binary=https://example.com/a=b")]).unwrap();
+        assert_eq!(links, vec![json!({ "name": "binary", "url": "https://example.com/a=b" })]);
+        assert!(parse_asset_links(&[String::from("binary")]).is_err());
+    }
+}
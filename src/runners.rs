@@ -0,0 +1,208 @@
+use std::error::Error;
+use colored::Colorize;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, Value};
+
+use crate::client::{encode_path, fetch_all_pages, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_runners(token: &str, project: Option<String>, group: Option<String>, all: bool, details: bool) -> Result<(), Box<dyn Error>> {
+    // Without a scope we list the runners the token's user owns
+    let url = match (project, group) {
+        (Some(project), _) => {
+            let project_id = fetch_project_id_by_name(token, &project).await?;
+            format!("https://gitlab.com/api/v4/projects/{}/runners", project_id)
+        }
+        (None, Some(group)) => format!("https://gitlab.com/api/v4/groups/{}/runners", encode_path(&group)),
+        (None, None) if all => String::from("https://gitlab.com/api/v4/runners/all"),
+        (None, None) => String::from("https://gitlab.com/api/v4/runners"),
+    };
+    let runners = fetch_all_pages(token, &url)?;
+
+    let mut header = vec![
+        Cell::new("ID").add_attribute(Attribute::Bold),
+        Cell::new("Description").add_attribute(Attribute::Bold),
+        Cell::new("Type").add_attribute(Attribute::Bold),
+        Cell::new("Status").add_attribute(Attribute::Bold),
+    ];
+    if details {
+        header.push(Cell::new("Tags").add_attribute(Attribute::Bold));
+        header.push(Cell::new("Version").add_attribute(Attribute::Bold));
+        header.push(Cell::new("Last Contact").add_attribute(Attribute::Bold));
+    }
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+
+    for runner in runners {
+        let runner_id = runner["id"].as_u64().unwrap_or_default();
+        let mut row = vec![
+            Cell::new(runner_id.to_string()),
+            Cell::new(runner["description"].as_str().unwrap_or("")),
+            Cell::new(runner["runner_type"].as_str().unwrap_or("")),
+            status_cell(&runner),
+        ];
+        // Tags, version and last contact are only returned by the detail endpoint,
+        // one request per runner, so they are only fetched with --details
+        if details {
+            match fetch_runner(token, runner_id) {
+                Ok(runner) => {
+                    row.push(Cell::new(join_tags(&runner)));
+                    row.push(Cell::new(runner["version"].as_str().unwrap_or("")));
+                    row.push(Cell::new(runner["contacted_at"].as_str().unwrap_or("")));
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    row.extend([Cell::new("-"), Cell::new("-"), Cell::new("-")]);
+                }
+            }
+        }
+        table.add_row(row);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn view_runner(token: &str, runner_id: u64, limit: usize) -> Result<(), Box<dyn Error>> {
+    let runner = fetch_runner(token, runner_id)?;
+    let status = runner_status(&runner);
+    let status = match status {
+        "online" => status.green(),
+        "paused" => status.yellow(),
+        _ => status.red(),
+    };
+
+    println!("{} {}  [{}]", format!("Runner {}", runner_id).bold(), runner["description"].as_str().unwrap_or(""), status);
+    println!("Type: {}", runner["runner_type"].as_str().unwrap_or(""));
+    println!("Tags: {}", join_tags(&runner));
+    println!("Runs untagged jobs: {}", runner["run_untagged"].as_bool().unwrap_or(false));
+    println!(
+        "Version: {} ({} {})",
+        runner["version"].as_str().unwrap_or("-"),
+        runner["platform"].as_str().unwrap_or(""),
+        runner["architecture"].as_str().unwrap_or("")
+    );
+    println!("Last contact at \"{}\"", runner["contacted_at"].as_str().unwrap_or("-"));
+    if let Some(projects) = runner["projects"].as_array().filter(|projects| !projects.is_empty()) {
+        let names: Vec<&str> = projects.iter().filter_map(|project| project["path_with_namespace"].as_str()).collect();
+        println!("Projects: {}", names.join(", "));
+    }
+
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/runners/{}/jobs", runner_id))
+        .query(&[("order_by", "id"), ("sort", "desc"), ("per_page", &limit.to_string())])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        eprintln!("Failed to fetch jobs for runner {}: {}", runner_id, response.status());
+        return Ok(());
+    }
+    let jobs: Vec<Value> = response.json()?;
+
+    println!("\n{}", "Recent jobs".bold());
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Job ID").add_attribute(Attribute::Bold),
+            Cell::new("Project").add_attribute(Attribute::Bold),
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+            Cell::new("Duration").add_attribute(Attribute::Bold),
+            Cell::new("Started At").add_attribute(Attribute::Bold),
+        ]);
+    for job in jobs {
+        let status_cell = match job["status"].as_str().unwrap_or("") {
+            "success" => Cell::new("success").fg(Color::Green),
+            "failed" => Cell::new("failed").fg(Color::Red),
+            status => Cell::new(status).fg(Color::Yellow),
+        };
+        table.add_row(vec![
+            Cell::new(job["id"].as_i64().unwrap_or_default().to_string()),
+            Cell::new(job["project"]["path_with_namespace"].as_str().unwrap_or("")),
+            Cell::new(job["name"].as_str().unwrap_or("")),
+            status_cell,
+            Cell::new(job["duration"].as_f64().unwrap_or_default().to_string()),
+            Cell::new(job["started_at"].as_str().unwrap_or("")),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+// Pause or resume a runner so it stops or starts picking up jobs
+pub async fn set_runner_paused(token: &str, runner_id: u64, paused: bool) -> Result<(), Box<dyn Error>> {
+    let response = API_CLIENT
+        .put(format!("https://gitlab.com/api/v4/runners/{}", runner_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "paused": paused }))
+        .send()?;
+
+    if response.status().is_success() {
+        let state = if paused { "paused" } else { "resumed" };
+        println!("Runner {} {}.", runner_id, state);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to update runner {}: {}", runner_id, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_runner(token: &str, runner_id: u64, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete runner {}", runner_id);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/runners/{}", runner_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Runner {} deleted successfully.", runner_id);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete runner: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+fn fetch_runner(token: &str, runner_id: u64) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/runners/{}", runner_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
+    } else {
+        let error_message = format!("Failed to fetch runner {}: {}", runner_id, response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+fn runner_status(runner: &Value) -> &str {
+    if runner["paused"].as_bool().unwrap_or(false) {
+        "paused"
+    } else {
+        runner["status"].as_str().unwrap_or("")
+    }
+}
+
+fn status_cell(runner: &Value) -> Cell {
+    let status = runner_status(runner);
+    match status {
+        "online" => Cell::new(status).fg(Color::Green),
+        "paused" => Cell::new(status).fg(Color::Yellow),
+        _ => Cell::new(status).fg(Color::Red),
+    }
+}
+
+fn join_tags(runner: &Value) -> String {
+    runner["tag_list"]
+        .as_array()
+        .map(|tags| tags.iter().filter_map(Value::as_str).collect::<Vec<&str>>().join(", "))
+        .unwrap_or_default()
+}
@@ -0,0 +1,315 @@
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use serde_json::{json, Map, Value};
+
+use crate::client::{fetch_all_pages, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::pipelines::print_pipeline;
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_schedules(token: &str, project_name: &str) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let schedules = fetch_all_pages(
+        token,
+        &format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules", project_id),
+    )?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Schedule ID").add_attribute(Attribute::Bold),
+            Cell::new("Description").add_attribute(Attribute::Bold),
+            Cell::new("State").add_attribute(Attribute::Bold),
+            Cell::new("Cron").add_attribute(Attribute::Bold),
+            Cell::new("Ref").add_attribute(Attribute::Bold),
+            Cell::new("Next Run At").add_attribute(Attribute::Bold),
+            Cell::new("Last Pipeline").add_attribute(Attribute::Bold),
+            Cell::new("Variables").add_attribute(Attribute::Bold),
+        ]);
+
+    for schedule in schedules {
+        // The list endpoint leaves out the last pipeline and variables, so fetch each schedule
+        let schedule_id = schedule["id"].as_u64().unwrap_or_default();
+        let (schedule, details_cell) = match fetch_schedule(token, project_id, schedule_id) {
+            Ok(details) => (details, None),
+            Err(e) => {
+                eprintln!("{}", e);
+                (schedule, Some(Cell::new("unknown").fg(Color::Yellow)))
+            }
+        };
+
+        let state_cell = if schedule["active"].as_bool().unwrap_or(false) {
+            Cell::new("active").fg(Color::Green)
+        } else {
+            Cell::new("inactive").fg(Color::Red)
+        };
+        let last_pipeline = &schedule["last_pipeline"];
+        let last_pipeline_cell = match (&details_cell, last_pipeline["status"].as_str()) {
+            (Some(unknown), _) => unknown.clone(),
+            (None, None) => Cell::new("never run"),
+            (None, Some(status)) => {
+                let cell = Cell::new(format!("{} {}", last_pipeline["id"], status));
+                match status {
+                    "success" => cell.fg(Color::Green),
+                    "failed" => cell.fg(Color::Red),
+                    _ => cell,
+                }
+            }
+        };
+        let variables_cell = match &details_cell {
+            Some(unknown) => unknown.clone(),
+            None => {
+                let keys: Vec<&str> = schedule["variables"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|variable| variable["key"].as_str())
+                    .collect();
+                Cell::new(if keys.is_empty() { String::from("-") } else { keys.join(", ") })
+            }
+        };
+        table.add_row(vec![
+            Cell::new(schedule_id.to_string()),
+            Cell::new(schedule["description"].as_str().unwrap_or("")),
+            state_cell,
+            Cell::new(format!(
+                "{} ({})",
+                schedule["cron"].as_str().unwrap_or(""),
+                schedule["cron_timezone"].as_str().unwrap_or("UTC")
+            )),
+            Cell::new(schedule["ref"].as_str().unwrap_or("")),
+            Cell::new(schedule["next_run_at"].as_str().unwrap_or("-")),
+            last_pipeline_cell,
+            variables_cell,
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_schedule(
+    token: &str,
+    project_name: &str,
+    description: &str,
+    cron: &str,
+    timezone: &str,
+    reference: &str,
+    active: bool,
+    variables: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let variables = parse_schedule_variables(&variables)?;
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "description": description,
+            "cron": cron,
+            "cron_timezone": timezone,
+            "ref": reference,
+            "active": active,
+        }))
+        .send()?;
+
+    if !response.status().is_success() {
+        let error_message = format!("Failed to create schedule: {}", response.text()?);
+        return Err(Box::from(error_message));
+    }
+    let schedule: Value = response.json()?;
+    let schedule_id = schedule["id"].as_u64().unwrap_or_default();
+
+    for (key, value) in variables {
+        if let Err(e) = set_schedule_variable(token, project_id, schedule_id, &key, &value, false) {
+            // Do not leave a schedule behind that runs without its variables
+            let rollback = API_CLIENT
+                .delete(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}", project_id, schedule_id))
+                .header("Authorization", format!("Bearer {}", token))
+                .send();
+            let error_message = match rollback {
+                Ok(response) if response.status().is_success() => {
+                    format!("{}, schedule {} was deleted again", e, schedule_id)
+                }
+                _ => format!("{}, schedule {} was created without all of its variables and could not be deleted", e, schedule_id),
+            };
+            return Err(Box::from(error_message));
+        }
+    }
+    println!("Schedule {} was created successfully", schedule_id);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_schedule(
+    token: &str,
+    project_name: &str,
+    schedule_id: u64,
+    description: Option<String>,
+    cron: Option<String>,
+    timezone: Option<String>,
+    reference: Option<String>,
+    active: Option<bool>,
+    variables: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let variables = parse_schedule_variables(&variables)?;
+
+    let mut body = Map::new();
+    if let Some(description) = description {
+        body.insert(String::from("description"), json!(description));
+    }
+    if let Some(cron) = cron {
+        body.insert(String::from("cron"), json!(cron));
+    }
+    if let Some(timezone) = timezone {
+        body.insert(String::from("cron_timezone"), json!(timezone));
+    }
+    if let Some(reference) = reference {
+        body.insert(String::from("ref"), json!(reference));
+    }
+    if let Some(active) = active {
+        body.insert(String::from("active"), json!(active));
+    }
+
+    if !body.is_empty() {
+        let response = API_CLIENT
+            .put(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}", project_id, schedule_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&Value::Object(body))
+            .send()?;
+        if !response.status().is_success() {
+            let error_message = format!("Failed to update schedule: {}", response.text()?);
+            return Err(Box::from(error_message));
+        }
+    }
+
+    if !variables.is_empty() {
+        let existing = fetch_schedule(token, project_id, schedule_id)?;
+        for (key, value) in variables {
+            let exists = existing["variables"]
+                .as_array()
+                .is_some_and(|variables| variables.iter().any(|variable| variable["key"].as_str() == Some(key.as_str())));
+            set_schedule_variable(token, project_id, schedule_id, &key, &value, exists)?;
+        }
+    }
+    println!("Schedule {} was updated successfully", schedule_id);
+    Ok(())
+}
+
+pub async fn delete_schedule(token: &str, project_name: &str, schedule_id: u64, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete pipeline schedule {} in project `{}`", schedule_id, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}", project_id, schedule_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Schedule {} deleted successfully.", schedule_id);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete schedule: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+// Play a schedule now and print the pipeline it creates
+pub async fn run_schedule(token: &str, project_name: &str, schedule_id: u64) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let pipelines_url = format!(
+        "https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}/pipelines",
+        project_id, schedule_id
+    );
+    let previous_id = latest_schedule_pipeline(token, &pipelines_url)?
+        .and_then(|pipeline| pipeline["id"].as_u64());
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}/play", project_id, schedule_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        let error_message = format!("Failed to run schedule: {}", response.text()?);
+        return Err(Box::from(error_message));
+    }
+
+    // The play endpoint only queues the run, wait for the pipeline to appear
+    for _ in 0..10 {
+        if let Some(pipeline) = latest_schedule_pipeline(token, &pipelines_url)? {
+            if pipeline["id"].as_u64() != previous_id {
+                print_pipeline(&pipeline);
+                return Ok(());
+            }
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    println!("Schedule {} was queued, its pipeline has not been created yet.", schedule_id);
+    Ok(())
+}
+
+fn fetch_schedule(token: &str, project_id: u64, schedule_id: u64) -> Result<Value, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}", project_id, schedule_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if response.status().is_success() {
+        Ok(response.json()?)
+    } else {
+        let error_message = format!("Failed to fetch schedule {}: {}", schedule_id, response.status());
+        Err(Box::from(error_message))
+    }
+}
+
+fn latest_schedule_pipeline(token: &str, pipelines_url: &str) -> Result<Option<Value>, Box<dyn Error>> {
+    let response = API_CLIENT
+        .get(pipelines_url)
+        .query(&[("sort", "desc"), ("per_page", "1")])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to fetch schedule pipelines: {}", response.status())));
+    }
+    let pipelines: Vec<Value> = response.json()?;
+    Ok(pipelines.into_iter().next())
+}
+
+fn set_schedule_variable(token: &str, project_id: u64, schedule_id: u64, key: &str, value: &str, exists: bool) -> Result<(), Box<dyn Error>> {
+    let variables_url = format!(
+        "https://gitlab.com/api/v4/projects/{}/pipeline_schedules/{}/variables",
+        project_id, schedule_id
+    );
+    let request = if exists {
+        API_CLIENT.put(format!("{}/{}", variables_url, key)).json(&json!({ "value": value }))
+    } else {
+        API_CLIENT.post(&variables_url).json(&json!({ "key": key, "value": value }))
+    };
+    let response = request
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("variable {} was set on schedule {}", key, schedule_id);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to set schedule variable {}: {}", key, response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+// Parse `KEY=VALUE` pairs given with --variable
+fn parse_schedule_variables(variables: &[String]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    variables
+        .iter()
+        .map(|variable| match variable.split_once('=') {
+            Some((key, value)) => Ok((key.to_string(), value.to_string())),
+            None => Err(Box::from(format!("Invalid variable '{}', expected KEY=VALUE", variable))),
+        })
+        .collect()
+}
@@ -0,0 +1,45 @@
+use std::error::Error;
+
+use crate::client::encode_path;
+use crate::git_remote::resolve_project;
+use crate::projects::fetch_project_id_by_name;
+
+// Which project, group or instance a variables or members command operates on
+pub enum Scope {
+    Project(String),
+    Group(String),
+    Instance,
+}
+
+impl Scope {
+    pub fn from_args(project: Option<String>, group: Option<String>, instance: bool) -> Result<Scope, Box<dyn Error>> {
+        match (project, group, instance) {
+            (Some(project), None, false) => Ok(Scope::Project(project)),
+            (None, Some(group), false) => Ok(Scope::Group(group)),
+            (None, None, true) => Ok(Scope::Instance),
+            (None, None, false) => Ok(Scope::Project(resolve_project(&None)?)),
+            _ => Err(Box::from("Specify exactly one of a project, a group or the instance")),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Scope::Project(name) => format!("project `{}`", name),
+            Scope::Group(path) => format!("group `{}`", path),
+            Scope::Instance => String::from("the instance"),
+        }
+    }
+
+    // URL of a project or group resource such as `variables` or `members`,
+    // the instance has no common prefix so callers map it themselves
+    pub async fn resource_url(&self, token: &str, resource: &str) -> Result<String, Box<dyn Error>> {
+        match self {
+            Scope::Project(name) => {
+                let project_id = fetch_project_id_by_name(token, name).await?;
+                Ok(format!("https://gitlab.com/api/v4/projects/{}/{}", project_id, resource))
+            }
+            Scope::Group(path) => Ok(format!("https://gitlab.com/api/v4/groups/{}/{}", encode_path(path), resource)),
+            Scope::Instance => Err(Box::from(format!("{} cannot be managed on the instance", resource))),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::error::Error;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+use serde_json::{json, Map, Value};
+
+use crate::client::{encode_path, fetch_all_pages_with_query, API_CLIENT};
+use crate::confirm::confirm_destructive;
+use crate::projects::fetch_project_id_by_name;
+
+pub async fn list_tags(token: &str, project_name: &str, search: Option<String>) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let url = format!("https://gitlab.com/api/v4/projects/{}/repository/tags", project_id);
+    let query: Vec<(&str, &str)> = search.iter().map(|search| ("search", search.as_str())).collect();
+    let tags = fetch_all_pages_with_query(token, &url, &query)?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Commit").add_attribute(Attribute::Bold),
+            Cell::new("Message").add_attribute(Attribute::Bold),
+            Cell::new("Release").add_attribute(Attribute::Bold),
+            Cell::new("Created At").add_attribute(Attribute::Bold),
+        ]);
+
+    for tag in tags {
+        table.add_row(vec![
+            Cell::new(tag["name"].as_str().unwrap_or("")),
+            Cell::new(format!(
+                "{} {}",
+                tag["commit"]["short_id"].as_str().unwrap_or(""),
+                tag["commit"]["title"].as_str().unwrap_or("")
+            )),
+            Cell::new(tag["message"].as_str().unwrap_or("")),
+            Cell::new(if tag["release"].is_null() { "no" } else { "yes" }),
+            Cell::new(tag["commit"]["created_at"].as_str().unwrap_or("")),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+pub async fn create_tag(token: &str, project_name: &str, name: &str, reference: &str, message: Option<String>) -> Result<(), Box<dyn Error>> {
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+
+    let mut body = Map::new();
+    body.insert(String::from("tag_name"), json!(name));
+    body.insert(String::from("ref"), json!(reference));
+    // A message turns the tag into an annotated tag
+    if let Some(message) = message {
+        body.insert(String::from("message"), json!(message));
+    }
+
+    let response = API_CLIENT
+        .post(format!("https://gitlab.com/api/v4/projects/{}/repository/tags", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&Value::Object(body))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Tag `{}` was created from `{}`", name, reference);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to create tag: {}", response.text()?);
+        Err(Box::from(error_message))
+    }
+}
+
+pub async fn delete_tag(token: &str, project_name: &str, name: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete tag `{}` in project `{}`", name, project_name);
+    if !confirm_destructive(&action, assume_yes)? {
+        return Ok(());
+    }
+    let project_id = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .delete(format!("https://gitlab.com/api/v4/projects/{}/repository/tags/{}", project_id, encode_path(name)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if response.status().is_success() {
+        println!("Tag `{}` deleted successfully.", name);
+        Ok(())
+    } else {
+        let error_message = format!("Failed to delete tag: {}", response.status());
+        Err(Box::from(error_message))
+    }
+}
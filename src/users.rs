@@ -0,0 +1,33 @@
+use std::error::Error;
+use serde_json::Value;
+
+use crate::client::API_CLIENT;
+
+// Look up a user's numeric ID from their username
+pub async fn fetch_user_id_by_username(token: &str, username: &str) -> Result<u64, Box<dyn Error>> {
+    let username = username.trim_start_matches('@');
+    let response = API_CLIENT
+        .get("https://gitlab.com/api/v4/users")
+        .query(&[("username", username)])
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to look up user '{}': {}", username, response.status())));
+    }
+
+    let users: Vec<Value> = response.json()?;
+    users
+        .first()
+        .and_then(|user| user.get("id"))
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Box::from(format!("User '{}' not found", username)))
+}
+
+pub async fn fetch_user_ids(token: &str, usernames: &[String]) -> Result<Vec<u64>, Box<dyn Error>> {
+    let mut ids = Vec::new();
+    for username in usernames {
+        ids.push(fetch_user_id_by_username(token, username).await?);
+    }
+    Ok(ids)
+}
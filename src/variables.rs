@@ -1,16 +1,37 @@
+use std::collections::HashSet;
 use std::error::Error;
 use comfy_table::presets::UTF8_FULL;
-use comfy_table::{Attribute, Cell, Table};
-use serde_json::Value;
-use std::io::{self, Write};
+use comfy_table::{Attribute, Cell, Color, Table};
+use serde_json::{json, Map, Value};
 
-use crate::client::API_CLIENT;
+use crate::client::{encode_path, fetch_all_pages, API_CLIENT};
+use crate::confirm::confirm_destructive;
 use crate::projects::fetch_project_id_by_name;
+use crate::scope::Scope;
 
-pub async fn list_project_variables(token: &str, project_name: &str) -> Result<(), Box<dyn Error>> {
-    let project_id: u64 = fetch_project_id_by_name(token, project_name).await?;
+// Attributes carried over when a variable is copied to another project or group
+const VARIABLE_ATTRIBUTES: [&str; 8] = [
+    "key",
+    "value",
+    "variable_type",
+    "protected",
+    "masked",
+    "raw",
+    "environment_scope",
+    "description",
+];
+
+async fn variables_url(scope: &Scope, token: &str) -> Result<String, Box<dyn Error>> {
+    match scope {
+        Scope::Instance => Ok(String::from("https://gitlab.com/api/v4/admin/ci/variables")),
+        _ => scope.resource_url(token, "variables").await,
+    }
+}
+
+pub async fn list_project_variables(token: &str, scope: &Scope) -> Result<(), Box<dyn Error>> {
+    let variables_url = variables_url(scope, token).await?;
     let response = API_CLIENT
-        .get(format!("https://gitlab.com/api/v4/projects/{}/variables", project_id))
+        .get(&variables_url)
         .header("Authorization", format!("Bearer {}", token))
         .send()?;
     if response.status().is_success() {
@@ -30,16 +51,17 @@ pub async fn list_project_variables(token: &str, project_name: &str) -> Result<(
         }
         println!("{table}");
     } else {
-        eprintln!("Failed to fetch variables for this project. Response={}", response.status());
+        eprintln!("Failed to fetch variables for {}. Response={}", scope.describe(), response.status());
     }
     Ok(())
 }
 
-pub async fn set_project_variables(token: &str, project_name: &str, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
-    let project_id: u64 = fetch_project_id_by_name(token, project_name).await?;
+pub async fn set_project_variables(token: &str, scope: &Scope, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    let variables_url = variables_url(scope, token).await?;
     let response = API_CLIENT
-        .post(format!("https://gitlab.com/api/v4/projects/{}/variables/?key={}&value={}", project_id, key, value))
+        .post(&variables_url)
         .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "key": key, "value": value }))
         .send()?;
     if response.status().is_success() {
         println!("variable {} was set successfully", key);
@@ -50,11 +72,12 @@ pub async fn set_project_variables(token: &str, project_name: &str, key: &str, v
     Ok(())
 }
 
-pub async fn update_project_variables(token: &str, project_name: &str, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
-    let project_id: u64 = fetch_project_id_by_name(token, project_name).await?;
+pub async fn update_project_variables(token: &str, scope: &Scope, key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    let variables_url = variables_url(scope, token).await?;
     let response = API_CLIENT
-        .put(format!("https://gitlab.com/api/v4/projects/{}/variables/{}?value={}", project_id, key, value))
+        .put(format!("{}/{}", variables_url, key))
         .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "value": value }))
         .send()?;
     if response.status().is_success() {
         println!("variable {} was updated successfully", key);
@@ -63,19 +86,16 @@ pub async fn update_project_variables(token: &str, project_name: &str, key: &str
     }
     Ok(())
 }
-pub async fn delete_project_variables(token: &str, project_name: &str, key: &str) -> Result<(), Box<dyn Error>> {
-    println!("Are you sure you want to delete the variable `{}` in project `{}`? Type 'DELETE' to confirm:", key, project_name);
-    let mut confirmation = String::new();
-    io::stdin().read_line(&mut confirmation)?;
-    if confirmation.trim() != "DELETE" {
-        println!("Delete operation aborted.");
+pub async fn delete_project_variables(token: &str, scope: &Scope, key: &str, assume_yes: bool) -> Result<(), Box<dyn Error>> {
+    let action = format!("delete the variable `{}` in {}", key, scope.describe());
+    if !confirm_destructive(&action, assume_yes)? {
         return Ok(());
     }
-    let project_id: u64 = fetch_project_id_by_name(token, project_name).await?;
+    let variables_url = variables_url(scope, token).await?;
 
     // Proceed with the delete request
     let response = API_CLIENT
-        .delete(format!("https://gitlab.com/api/v4/projects/{}/variables/{}", project_id, key))
+        .delete(format!("{}/{}", variables_url, key))
         .header("Authorization", format!("Bearer {}", token))
         .send()?;
 
@@ -86,4 +106,198 @@ pub async fn delete_project_variables(token: &str, project_name: &str, key: &str
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// Show every variable a project's jobs can see, including those inherited from
+// parent groups and the instance, and which definition takes precedence
+pub async fn list_effective_variables(token: &str, project_name: &str) -> Result<(), Box<dyn Error>> {
+    let definitions = fetch_effective_variables(token, project_name).await?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL)
+        .set_header(vec![
+            Cell::new("Key").add_attribute(Attribute::Bold),
+            Cell::new("Environment").add_attribute(Attribute::Bold),
+            Cell::new("Value").add_attribute(Attribute::Bold),
+            Cell::new("Defined In").add_attribute(Attribute::Bold),
+            Cell::new("Status").add_attribute(Attribute::Bold),
+        ]);
+
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for (source, variable) in definitions {
+        let key = variable["key"].as_str().unwrap_or("").to_string();
+        let environment = variable["environment_scope"].as_str().unwrap_or("*").to_string();
+        let value = if variable["masked"].as_bool().unwrap_or(false) {
+            "[masked]"
+        } else {
+            variable["value"].as_str().unwrap_or("")
+        };
+        let status_cell = if seen.insert((key.clone(), environment.clone())) {
+            Cell::new("effective").fg(Color::Green)
+        } else {
+            Cell::new("overridden").fg(Color::DarkGrey)
+        };
+        table.add_row(vec![
+            Cell::new(&key),
+            Cell::new(&environment),
+            Cell::new(value),
+            Cell::new(&source),
+            status_cell,
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+// Every definition visible to a project's jobs as (source, variable), ordered so
+// that the first definition of each key and environment scope takes precedence
+pub async fn fetch_effective_variables(token: &str, project_name: &str) -> Result<Vec<(String, Value)>, Box<dyn Error>> {
+    let project_id: u64 = fetch_project_id_by_name(token, project_name).await?;
+    let response = API_CLIENT
+        .get(format!("https://gitlab.com/api/v4/projects/{}", project_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()?;
+    if !response.status().is_success() {
+        return Err(Box::from(format!("Failed to fetch project details: {}", response.status())));
+    }
+    let project: Value = response.json()?;
+
+    // Sources ordered from highest to lowest precedence
+    let mut sources = vec![(
+        format!("project {}", project_name),
+        format!("https://gitlab.com/api/v4/projects/{}/variables", project_id),
+    )];
+    if project["namespace"]["kind"].as_str() == Some("group") {
+        let namespace = project["namespace"]["full_path"].as_str().unwrap_or("");
+        let segments: Vec<&str> = namespace.split('/').collect();
+        for depth in (1..=segments.len()).rev() {
+            let group_path = segments[..depth].join("/");
+            sources.push((
+                format!("group {}", group_path),
+                format!("https://gitlab.com/api/v4/groups/{}/variables", encode_path(&group_path)),
+            ));
+        }
+    }
+    sources.push((
+        String::from("instance"),
+        String::from("https://gitlab.com/api/v4/admin/ci/variables"),
+    ));
+
+    let mut definitions = Vec::new();
+    for (source, url) in &sources {
+        match fetch_all_pages(token, url) {
+            Ok(variables) => {
+                for variable in variables {
+                    definitions.push((source.clone(), variable));
+                }
+            }
+            Err(e) => eprintln!("Skipping variables from {}: {}", source, e),
+        }
+    }
+
+    // Keep precedence order within each key so the first definition seen wins
+    definitions.sort_by(|(_, a), (_, b)| {
+        let key_a = (a["key"].as_str().unwrap_or(""), a["environment_scope"].as_str().unwrap_or("*"));
+        let key_b = (b["key"].as_str().unwrap_or(""), b["environment_scope"].as_str().unwrap_or("*"));
+        key_a.cmp(&key_b)
+    });
+    Ok(definitions)
+}
+
+pub async fn copy_variables(
+    token: &str,
+    from: Scope,
+    to: Scope,
+    keys: Option<Vec<String>>,
+    overwrite: bool,
+) -> Result<(), Box<dyn Error>> {
+    let source_url = variables_url(&from, token).await?;
+    let target_url = variables_url(&to, token).await?;
+
+    let mut variables = fetch_all_pages(token, &source_url)?;
+    if let Some(keys) = &keys {
+        for key in keys {
+            if !variables.iter().any(|variable| variable["key"].as_str() == Some(key.as_str())) {
+                eprintln!("Variable `{}` does not exist in {}", key, from.describe());
+            }
+        }
+        variables.retain(|variable| {
+            variable["key"].as_str().is_some_and(|key| keys.iter().any(|wanted| wanted == key))
+        });
+    }
+
+    // Variables are unique per key and environment scope in the target
+    let existing: HashSet<(String, String)> = fetch_all_pages(token, &target_url)?
+        .iter()
+        .map(|variable| (
+            variable["key"].as_str().unwrap_or("").to_string(),
+            variable["environment_scope"].as_str().unwrap_or("*").to_string(),
+        ))
+        .collect();
+
+    let (mut copied, mut skipped, mut held_back) = (0, 0, 0);
+    let mut failed: Vec<String> = Vec::new();
+    for variable in variables {
+        let key = variable["key"].as_str().unwrap_or("").to_string();
+        let scope = variable["environment_scope"].as_str().unwrap_or("*").to_string();
+
+        // Hidden variables never expose their value through the API
+        if variable["value"].is_null() {
+            eprintln!("Skipping `{}`: its value is hidden and cannot be copied", key);
+            skipped += 1;
+            continue;
+        }
+
+        let mut body = Map::new();
+        for attribute in VARIABLE_ATTRIBUTES {
+            if let Some(value) = variable.get(attribute).filter(|value| !value.is_null()) {
+                body.insert(attribute.to_string(), value.clone());
+            }
+        }
+
+        let request = if existing.contains(&(key.clone(), scope.clone())) {
+            if !overwrite {
+                println!("Skipping `{}` ({}): already exists in {}, use --overwrite to replace it", key, scope, to.describe());
+                skipped += 1;
+                continue;
+            }
+            API_CLIENT
+                .put(format!("{}/{}", target_url, key))
+                .query(&[("filter[environment_scope]", scope.as_str())])
+        } else {
+            API_CLIENT.post(&target_url)
+        };
+
+        let Some(response) = request
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&Value::Object(body))
+            .send_unless_dry_run()?
+        else {
+            held_back += 1;
+            continue;
+        };
+
+        if response.status().is_success() {
+            println!("variable {} ({}) was copied successfully", key, scope);
+            copied += 1;
+        } else {
+            eprintln!("Failed to copy variable {} ({}): {}", key, scope, response.text()?);
+            failed.push(format!("{} ({})", key, scope));
+        }
+    }
+
+    if held_back > 0 {
+        println!("Would copy {} variable(s) from {} to {}, {} skipped", held_back, from.describe(), to.describe(), skipped);
+        return Ok(());
+    }
+    println!(
+        "Copied {} variable(s) from {} to {}, {} skipped, {} failed",
+        copied, from.describe(), to.describe(), skipped, failed.len()
+    );
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        let error_message = format!("Failed to copy {} variable(s): {}", failed.len(), failed.join(", "));
+        Err(Box::from(error_message))
+    }
+}